@@ -0,0 +1,52 @@
+//! Measures the cost of a frame of enemy behavior with a crowd of enemies.
+//!
+//! Run with `cargo bench --bench enemies`.
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use bevy_jam_5::game::{
+    cycle::DayCycle,
+    enemies::{self, Enemy, EnemyArchetype},
+    spawn::player::Player,
+};
+use criterion::{criterion_group, criterion_main, Criterion};
+
+fn enemy_app(enemy_count: usize) -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, HierarchyPlugin, TransformPlugin));
+    app.add_plugins(enemies::plugin);
+    app.init_resource::<DayCycle>();
+    // Enemies move in fixed steps, so run exactly one per frame.
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(
+        Time::<Fixed>::default().timestep(),
+    ));
+
+    app.world_mut().spawn((Player, TransformBundle::default()));
+
+    // Pack the enemies closely so separation has plenty of work to do.
+    let columns = (enemy_count as f32).sqrt().ceil() as usize;
+    for index in 0..enemy_count {
+        let position = Vec2::new((index % columns) as f32, (index / columns) as f32) * 80.0
+            - Vec2::splat(columns as f32 * 40.0);
+        app.world_mut().spawn((
+            Enemy {
+                archetype: EnemyArchetype::Chaser,
+                max_speed: 800.0,
+                phase_speed: 1.0,
+                contact_damage: 1.0,
+            },
+            TransformBundle::from_transform(Transform::from_translation(position.extend(0.0))),
+        ));
+    }
+
+    // Let the transforms propagate before measuring.
+    app.update();
+    app
+}
+
+fn enemy_frame(c: &mut Criterion) {
+    let mut app = enemy_app(1000);
+    c.bench_function("enemy frame with 1000 enemies", |b| b.iter(|| app.update()));
+}
+
+criterion_group!(benches, enemy_frame);
+criterion_main!(benches);
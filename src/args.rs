@@ -0,0 +1,19 @@
+//! Read options from the command line. Web builds have no command line, so they use the defaults.
+
+/// The value passed with `--<name> <value>` or `--<name>=<value>`, if any.
+pub fn value(name: &str) -> Option<String> {
+    let flag = format!("--{name}");
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let Some(rest) = arg.strip_prefix(&flag) else {
+            continue;
+        };
+        if rest.is_empty() {
+            return args.next();
+        }
+        if let Some(value) = rest.strip_prefix('=') {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
@@ -1,8 +1,15 @@
+use std::marker::PhantomData;
+
 use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
     prelude::*,
     render::texture::{ImageLoaderSettings, ImageSampler},
     utils::HashMap,
 };
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{levels::LevelLayout, shop::ShopCatalog, species::PlantSpecies, weapons::Weapon};
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<HandleMap<ImageKey>>();
@@ -13,6 +20,18 @@ pub(super) fn plugin(app: &mut App) {
 
     app.register_type::<HandleMap<SoundtrackKey>>();
     app.init_resource::<HandleMap<SoundtrackKey>>();
+
+    app.register_type::<HandleMap<PlantKey>>();
+    app.init_resource::<HandleMap<PlantKey>>();
+
+    app.register_type::<HandleMap<CatalogKey>>();
+    app.init_resource::<HandleMap<CatalogKey>>();
+
+    app.register_type::<HandleMap<WeaponKey>>();
+    app.init_resource::<HandleMap<WeaponKey>>();
+
+    app.register_type::<HandleMap<LevelKey>>();
+    app.init_resource::<HandleMap<LevelKey>>();
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Reflect)]
@@ -102,6 +121,129 @@ impl FromWorld for HandleMap<SoundtrackKey> {
     }
 }
 
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Reflect, Serialize, Deserialize)]
+pub enum PlantKey {
+    Weed,
+    Flower,
+    Cactus,
+    Tuber,
+}
+
+impl PlantKey {
+    pub const ALL: [PlantKey; 4] = [
+        PlantKey::Weed,
+        PlantKey::Flower,
+        PlantKey::Cactus,
+        PlantKey::Tuber,
+    ];
+}
+
+impl AssetKey for PlantKey {
+    type Asset = PlantSpecies;
+}
+
+impl FromWorld for HandleMap<PlantKey> {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+        [
+            (PlantKey::Weed, asset_server.load("plants/weed.plant.ron")),
+            (
+                PlantKey::Flower,
+                asset_server.load("plants/flower.plant.ron"),
+            ),
+            (
+                PlantKey::Cactus,
+                asset_server.load("plants/cactus.plant.ron"),
+            ),
+            (PlantKey::Tuber, asset_server.load("plants/tuber.plant.ron")),
+        ]
+        .into()
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Reflect)]
+pub enum CatalogKey {
+    Shop,
+}
+
+impl AssetKey for CatalogKey {
+    type Asset = ShopCatalog;
+}
+
+impl FromWorld for HandleMap<CatalogKey> {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+        [(CatalogKey::Shop, asset_server.load("shop.catalog.ron"))].into()
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Reflect, Serialize, Deserialize)]
+pub enum WeaponKey {
+    Pistol,
+    Shotgun,
+    Lance,
+    Cannon,
+}
+
+impl AssetKey for WeaponKey {
+    type Asset = Weapon;
+}
+
+impl FromWorld for HandleMap<WeaponKey> {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+        [
+            (
+                WeaponKey::Pistol,
+                asset_server.load("weapons/pistol.weapon.ron"),
+            ),
+            (
+                WeaponKey::Shotgun,
+                asset_server.load("weapons/shotgun.weapon.ron"),
+            ),
+            (
+                WeaponKey::Lance,
+                asset_server.load("weapons/lance.weapon.ron"),
+            ),
+            (
+                WeaponKey::Cannon,
+                asset_server.load("weapons/cannon.weapon.ron"),
+            ),
+        ]
+        .into()
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default, Reflect, Serialize, Deserialize)]
+pub enum LevelKey {
+    #[default]
+    Field,
+    Garden,
+}
+
+impl LevelKey {
+    /// Every level, in the order they're offered on the title screen.
+    pub const ALL: [LevelKey; 2] = [LevelKey::Field, LevelKey::Garden];
+}
+
+impl AssetKey for LevelKey {
+    type Asset = LevelLayout;
+}
+
+impl FromWorld for HandleMap<LevelKey> {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+        [
+            (LevelKey::Field, asset_server.load("levels/field.level.ron")),
+            (
+                LevelKey::Garden,
+                asset_server.load("levels/garden.level.ron"),
+            ),
+        ]
+        .into()
+    }
+}
+
 pub trait AssetKey: Sized {
     type Asset: Asset;
 }
@@ -125,3 +267,56 @@ impl<K: AssetKey> HandleMap<K> {
             .all(|x| asset_server.is_loaded_with_dependencies(x))
     }
 }
+
+/// Loads any asset that can be deserialized from a RON file.
+pub struct RonAssetLoader<A> {
+    extensions: &'static [&'static str],
+    _phantom: PhantomData<fn() -> A>,
+}
+
+impl<A> RonAssetLoader<A> {
+    pub fn new(extensions: &'static [&'static str]) -> Self {
+        Self {
+            extensions,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum RonAssetLoaderError {
+    #[error("Could not load asset: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse RON: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+/// An asset that [`RonAssetLoader`] can load.
+pub trait RonAsset: Asset + for<'de> Deserialize<'de> {
+    /// Start loading the assets this one refers to by path, like images.
+    fn load_dependencies(&mut self, _load_context: &mut LoadContext) {}
+}
+
+impl<A: RonAsset> AssetLoader for RonAssetLoader<A> {
+    type Asset = A;
+    type Settings = ();
+    type Error = RonAssetLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let mut asset: A = ron::de::from_bytes(&bytes)?;
+        asset.load_dependencies(load_context);
+        Ok(asset)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        self.extensions
+    }
+}
@@ -1,7 +1,16 @@
-use bevy::{audio::PlaybackMode, prelude::*};
-use rand::seq::SliceRandom;
+use bevy::{
+    audio::{PlaybackMode, Volume},
+    prelude::*,
+};
+use rand::{seq::SliceRandom, Rng};
 
-use crate::game::assets::{HandleMap, SfxKey};
+use crate::{
+    game::{
+        assets::{HandleMap, SfxKey},
+        rng::GameRng,
+    },
+    settings::Settings,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.observe(play_sfx);
@@ -11,15 +20,18 @@ fn play_sfx(
     trigger: Trigger<PlaySfx>,
     mut commands: Commands,
     sfx_handles: Res<HandleMap<SfxKey>>,
+    settings: Res<Settings>,
+    mut rng: ResMut<GameRng>,
 ) {
     let sfx_key = match trigger.event() {
         PlaySfx::Key(key) => *key,
-        PlaySfx::RandomStep => random_step(),
+        PlaySfx::RandomStep => random_step(rng.cosmetic()),
     };
     commands.spawn(AudioSourceBundle {
         source: sfx_handles[&sfx_key].clone_weak(),
         settings: PlaybackSettings {
             mode: PlaybackMode::Despawn,
+            volume: Volume::new(settings.sfx_volume()),
             ..default()
         },
     });
@@ -32,9 +44,9 @@ pub enum PlaySfx {
     RandomStep,
 }
 
-fn random_step() -> SfxKey {
+fn random_step(rng: &mut impl Rng) -> SfxKey {
     [SfxKey::Step1, SfxKey::Step2, SfxKey::Step3, SfxKey::Step4]
-        .choose(&mut rand::thread_rng())
+        .choose(rng)
         .copied()
         .unwrap()
 }
@@ -1,10 +1,20 @@
-use bevy::{audio::PlaybackMode, prelude::*};
+use bevy::{
+    audio::{PlaybackMode, Volume},
+    prelude::*,
+};
 
-use crate::game::assets::{HandleMap, SoundtrackKey};
+use crate::{
+    game::assets::{HandleMap, SoundtrackKey},
+    settings::Settings,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<IsSoundtrack>();
     app.observe(play_soundtrack);
+    app.add_systems(
+        Update,
+        update_soundtrack_volume.run_if(resource_changed::<Settings>),
+    );
 }
 
 fn play_soundtrack(
@@ -12,6 +22,7 @@ fn play_soundtrack(
     mut commands: Commands,
     soundtrack_handles: Res<HandleMap<SoundtrackKey>>,
     soundtrack_query: Query<Entity, With<IsSoundtrack>>,
+    settings: Res<Settings>,
 ) {
     for entity in &soundtrack_query {
         commands.entity(entity).despawn_recursive();
@@ -26,6 +37,7 @@ fn play_soundtrack(
             source: soundtrack_handles[&soundtrack_key].clone_weak(),
             settings: PlaybackSettings {
                 mode: PlaybackMode::Loop,
+                volume: Volume::new(settings.music_volume()),
                 ..default()
             },
         },
@@ -46,3 +58,9 @@ pub enum PlaySoundtrack {
 #[derive(Component, Reflect)]
 #[reflect(Component)]
 struct IsSoundtrack;
+
+fn update_soundtrack_volume(settings: Res<Settings>, sinks: Query<&AudioSink, With<IsSoundtrack>>) {
+    for sink in &sinks {
+        sink.set_volume(settings.music_volume());
+    }
+}
@@ -4,19 +4,30 @@ use bevy::{
     color::palettes::css::WHITE, prelude::*, render::mesh::CircleMeshBuilder, sprite::Mesh2dHandle,
 };
 use bevy_rapier2d::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use crate::screen::Screen;
 
-use super::{health::Damage, layers};
+use super::{
+    collision_groups::OBSTACLE_GROUP,
+    health::{Damage, DamageType},
+    input::{Action, ActionState},
+    interpolation::InterpolateTranslation,
+    layers,
+    movement::{Facing, Knockback},
+    spawn::terrain::Wall,
+    status::{ApplyStatus, StatusEffect},
+    weapons::Weapon,
+};
 
-pub(super) fn plugin(app: &mut App) {
+pub fn plugin(app: &mut App) {
+    app.register_type::<AimMode>();
     app.observe(spawn_bullet);
     app.add_systems(Startup, init_bullet_assets);
     app.add_systems(
-        Update,
-        (fire_bullets, hit_test_bullets, move_bullets).chain(),
+        FixedUpdate,
+        (time_to_live, fire_bullets, hit_test_bullets, move_bullets).chain(),
     );
-    app.add_systems(PreUpdate, time_to_live);
 }
 
 #[derive(Resource)]
@@ -39,9 +50,17 @@ fn init_bullet_assets(
 #[derive(Component)]
 struct Bullet {
     damage: f32,
+    damage_type: DamageType,
+    source: Option<Entity>,
     velocity: Vec2,
     collision_groups: CollisionGroups,
     collider: Collider,
+    /// How many more entities the bullet passes through.
+    pierce: u32,
+    knockback: f32,
+    effects: Vec<StatusEffect>,
+    /// The entities the bullet has already passed through, so it doesn't hit them again.
+    hits: Vec<Entity>,
 }
 
 #[derive(Component, Reflect)]
@@ -50,25 +69,83 @@ struct TimeToLive {
     timer: Timer,
 }
 
+/// How a [`BulletSpawner`] picks the direction to fire in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub enum AimMode {
+    /// Fire at the nearest target within [`BulletSpawner::range`] that isn't behind an obstacle.
+    #[default]
+    AutoNearest,
+    /// Fire along [`Action::Aim`] while it's held, and at the cursor otherwise.
+    Cursor,
+    /// Fire in the direction the spawner's parent is [`Facing`].
+    MovementDirection,
+}
+
+impl AimMode {
+    /// The mode after this one, for cycling through them in the settings.
+    pub fn next(self) -> Self {
+        match self {
+            AimMode::AutoNearest => AimMode::Cursor,
+            AimMode::Cursor => AimMode::MovementDirection,
+            AimMode::MovementDirection => AimMode::AutoNearest,
+        }
+    }
+}
+
 #[derive(Component)]
 pub struct BulletSpawner {
-    pub bullet_damage: f32,
-    pub bullet_speed: f32,
-    pub bullet_radius: f32,
-    pub bullet_time_to_live: Duration,
+    pub weapon: Weapon,
+    pub aim: AimMode,
+    /// How far away [`AimMode::AutoNearest`] looks for targets.
+    pub range: f32,
+    /// Time until the next shot, or until a charged weapon can charge again.
     pub timer: Timer,
+    /// How long the current shot has been charged for.
+    pub charged: Duration,
     pub collision_groups: CollisionGroups,
 }
 
+impl BulletSpawner {
+    pub fn new(
+        weapon: Weapon,
+        aim: AimMode,
+        range: f32,
+        collision_groups: CollisionGroups,
+    ) -> Self {
+        let mode = if weapon.charge.is_some() {
+            TimerMode::Once
+        } else {
+            TimerMode::Repeating
+        };
+        Self {
+            timer: Timer::new(weapon.fire_interval(), mode),
+            charged: Duration::ZERO,
+            weapon,
+            aim,
+            range,
+            collision_groups,
+        }
+    }
+}
+
 #[derive(Event)]
 pub struct SpawnBullet {
     pub damage: f32,
+    pub damage_type: DamageType,
+    /// The entity that fired the bullet, credited with its hits.
+    pub source: Option<Entity>,
     pub position: Vec2,
     pub direction: Dir2,
     pub speed: f32,
     pub time_to_live: Duration,
     pub collision_groups: CollisionGroups,
     pub radius: f32,
+    /// How many entities the bullet passes through before it's despawned.
+    pub pierce: u32,
+    /// How fast a hit pushes the entity away.
+    pub knockback: f32,
+    /// Status effects applied to every entity the bullet hits.
+    pub effects: Vec<StatusEffect>,
 }
 
 fn spawn_bullet(
@@ -80,9 +157,15 @@ fn spawn_bullet(
     commands.spawn((
         Bullet {
             damage: trigger.event().damage,
+            damage_type: trigger.event().damage_type,
+            source: trigger.event().source,
             velocity: trigger.event().direction * trigger.event().speed,
             collision_groups: trigger.event().collision_groups,
             collider: Collider::ball(trigger.event().radius),
+            pierce: trigger.event().pierce,
+            knockback: trigger.event().knockback,
+            effects: trigger.event().effects.clone(),
+            hits: Vec::new(),
         },
         TimeToLive {
             timer: Timer::new(trigger.event().time_to_live, TimerMode::Once),
@@ -94,46 +177,152 @@ fn spawn_bullet(
                 .with_translation(trigger.event().position.extend(layers::BULLETS)),
             ..default()
         },
+        InterpolateTranslation::default(),
         StateScoped(Screen::Playing),
     ));
 }
 
 fn fire_bullets(
     time: Res<Time>,
-    mut spawners: Query<(&GlobalTransform, &mut BulletSpawner)>,
+    actions: Res<ActionState>,
+    mut spawners: Query<(
+        Entity,
+        &GlobalTransform,
+        &mut BulletSpawner,
+        Option<&Parent>,
+    )>,
+    transforms: Query<&GlobalTransform>,
+    facings: Query<&Facing>,
     rapier_context: Res<RapierContext>,
     mut commands: Commands,
 ) {
-    for (global_transform, mut spawner) in &mut spawners {
+    for (entity, global_transform, mut spawner, parent) in &mut spawners {
         spawner.timer.tick(time.delta());
-        if spawner.timer.just_finished() {
-            let filter = bevy_rapier2d::pipeline::QueryFilter::from(spawner.collision_groups);
-            let position = global_transform.translation().truncate();
-            if let Some((_entity, projection)) =
-                rapier_context.project_point(position, false, filter)
-            {
-                let direction = Dir2::new(projection.point - position).unwrap_or(Dir2::NORTH);
-                commands.trigger(SpawnBullet {
-                    position,
-                    damage: spawner.bullet_damage,
-                    direction,
-                    speed: spawner.bullet_speed,
-                    time_to_live: spawner.bullet_time_to_live,
-                    collision_groups: spawner.collision_groups,
-                    radius: spawner.bullet_radius.clone(),
-                });
+        let charge = spawner.weapon.charge;
+        let damage_multiplier = match charge {
+            None if spawner.timer.just_finished() => 1.0,
+            None => continue,
+            Some(charge) => {
+                // Charge while `Use` is held, and fire once it's released.
+                if !spawner.timer.finished() {
+                    continue;
+                }
+                if actions.pressed(Action::Use) {
+                    spawner.charged += time.delta();
+                    continue;
+                }
+                if spawner.charged.is_zero() {
+                    continue;
+                }
+                let fraction = (spawner.charged.as_secs_f32() / charge.time_secs).min(1.0);
+                1.0 + (charge.max_damage_multiplier - 1.0) * fraction
+            }
+        };
+
+        let position = global_transform.translation().truncate();
+        let direction = match spawner.aim {
+            AimMode::AutoNearest => {
+                nearest_visible_target(&rapier_context, &transforms, position, &spawner)
+                    .and_then(|target| Dir2::new(target - position).ok())
             }
+            AimMode::Cursor => Dir2::new(actions.axis(Action::Aim)).ok().or_else(|| {
+                actions
+                    .cursor()
+                    .and_then(|cursor| Dir2::new(cursor - position).ok())
+            }),
+            AimMode::MovementDirection => parent
+                .and_then(|parent| facings.get(parent.get()).ok())
+                .map(|facing| facing.0),
+        };
+        // Without a direction, a released charge is kept until there's something to fire at.
+        let Some(direction) = direction else {
+            continue;
+        };
+        if charge.is_some() {
+            spawner.charged = Duration::ZERO;
+            spawner.timer.reset();
+        }
+
+        // Spawners are usually held by the entity doing the shooting.
+        let source = parent.map_or(entity, Parent::get);
+        let weapon = &spawner.weapon;
+        for direction in weapon.shot_directions(direction) {
+            commands.trigger(SpawnBullet {
+                position,
+                damage: weapon.damage * damage_multiplier,
+                damage_type: weapon.damage_type,
+                source: Some(source),
+                direction,
+                speed: weapon.bullet_speed,
+                time_to_live: weapon.bullet_time_to_live(),
+                collision_groups: spawner.collision_groups,
+                radius: weapon.bullet_radius,
+                pierce: weapon.pierce,
+                knockback: weapon.knockback,
+                effects: weapon.on_hit.clone(),
+            });
         }
     }
 }
 
+/// Where the nearest target within the spawner's range is,
+/// skipping targets that are hidden behind an obstacle.
+fn nearest_visible_target(
+    rapier_context: &RapierContext,
+    transforms: &Query<&GlobalTransform>,
+    position: Vec2,
+    spawner: &BulletSpawner,
+) -> Option<Vec2> {
+    let filter =
+        bevy_rapier2d::pipeline::QueryFilter::from(spawner.collision_groups).exclude_sensors();
+    let mut targets = Vec::new();
+    rapier_context.intersections_with_shape(
+        position,
+        0.0,
+        &Collider::ball(spawner.range),
+        filter,
+        |entity| {
+            if let Ok(transform) = transforms.get(entity) {
+                targets.push(transform.translation().truncate());
+            }
+            true
+        },
+    );
+    targets.sort_by(|a, b| {
+        a.distance_squared(position)
+            .total_cmp(&b.distance_squared(position))
+    });
+
+    // Only obstacles block the line of sight. Other targets in the way would just be hit instead.
+    let obstacles = bevy_rapier2d::pipeline::QueryFilter::from(CollisionGroups {
+        memberships: spawner.collision_groups.memberships,
+        filters: OBSTACLE_GROUP,
+    })
+    .exclude_sensors();
+    targets.into_iter().find(|&target| {
+        let Ok(direction) = Dir2::new(target - position) else {
+            return true;
+        };
+        rapier_context
+            .cast_ray(
+                position,
+                *direction,
+                position.distance(target),
+                true,
+                obstacles,
+            )
+            .is_none()
+    })
+}
+
 fn hit_test_bullets(
     time: Res<Time>,
-    bullets: Query<(Entity, &Transform, &Bullet)>,
+    mut bullets: Query<(Entity, &Transform, &mut Bullet)>,
+    walls: Query<(), With<Wall>>,
     rapier_context: Res<RapierContext>,
     mut commands: Commands,
 ) {
-    for (entity, global_transform, bullet) in &bullets {
+    for (entity, global_transform, mut bullet) in &mut bullets {
         let position = global_transform.translation.truncate();
         let rotation = 0.0; // rotation in radians
         let options = ShapeCastOptions {
@@ -142,22 +331,57 @@ fn hit_test_bullets(
             stop_at_penetration: true,
             compute_impact_geometry_on_penetration: false,
         };
-        if let Some((hit_entity, _toi)) = rapier_context.cast_shape(
+        let not_hit_yet = |hit_entity: Entity| !bullet.hits.contains(&hit_entity);
+        // Walls stop every bullet, whatever it's meant to hit.
+        let filter = bevy_rapier2d::pipeline::QueryFilter::from(CollisionGroups {
+            memberships: bullet.collision_groups.memberships,
+            filters: bullet.collision_groups.filters | OBSTACLE_GROUP,
+        })
+        .predicate(&not_hit_yet);
+        let Some((hit_entity, _toi)) = rapier_context.cast_shape(
             position,
             rotation,
             bullet.velocity.normalize(),
             &bullet.collider,
             options,
-            bullet.collision_groups.into(),
-        ) {
+            filter,
+        ) else {
+            continue;
+        };
+        if walls.contains(hit_entity) {
             commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        commands.trigger_targets(
+            Damage {
+                amount: bullet.damage,
+                damage_type: bullet.damage_type,
+                source: bullet.source,
+            },
+            hit_entity,
+        );
+        for effect in &bullet.effects {
             commands.trigger_targets(
-                Damage {
-                    amount: bullet.damage,
+                ApplyStatus {
+                    effect: effect.clone(),
+                    source: bullet.source,
                 },
                 hit_entity,
             );
         }
+        if bullet.knockback > 0.0 {
+            // The hit may have killed the entity, so it could be gone already.
+            commands.entity(hit_entity).try_insert(Knockback {
+                velocity: bullet.velocity.normalize() * bullet.knockback,
+            });
+        }
+        if bullet.pierce == 0 {
+            commands.entity(entity).despawn_recursive();
+        } else {
+            bullet.pierce -= 1;
+            bullet.hits.push(hit_entity);
+        }
     }
 }
 
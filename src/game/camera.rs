@@ -0,0 +1,204 @@
+//! Move the camera along with the player and shake it when things get rough.
+//! The camera follows the player once it leaves a deadzone in the middle of the screen,
+//! and never shows anything outside of [`Level::bounds`].
+//! Trigger [`AddTrauma`] to shake it.
+
+use bevy::{prelude::*, transform::TransformSystem, window::PrimaryWindow};
+
+use super::{
+    health::Damaged,
+    interpolation::interpolate_translation,
+    movement::Dash,
+    spawn::{level::Level, player::Player},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<(CameraFollow, CameraShake)>();
+    app.observe(add_trauma);
+    app.observe(recenter_on_player);
+    app.observe(shake_on_player_damage);
+    app.observe(shake_on_dash);
+    app.add_systems(
+        PostUpdate,
+        (
+            zoom_to_level.run_if(resource_exists_and_changed::<Level>),
+            follow_player,
+            shake_camera,
+        )
+            .chain()
+            // Follow where the player is drawn, not where it was last simulated.
+            .after(interpolate_translation)
+            .before(TransformSystem::TransformPropagate),
+    );
+}
+
+/// How much trauma wears off per second.
+const TRAUMA_DECAY: f32 = 1.5;
+/// How far the camera is moved at full trauma, in pixels.
+const MAX_SHAKE_OFFSET: f32 = 150.0;
+/// How far the camera is rotated at full trauma, in radians.
+const MAX_SHAKE_ANGLE: f32 = 0.05;
+/// How many times per second the shake changes direction, roughly.
+const SHAKE_FREQUENCY: f32 = 20.0;
+
+/// Trauma added when the player is hurt.
+const DAMAGE_TRAUMA: f32 = 0.4;
+/// Trauma added when the player dashes.
+const DASH_TRAUMA: f32 = 0.15;
+/// Trauma added when a plant hatches into enemies.
+pub const HATCH_TRAUMA: f32 = 0.3;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct CameraFollow {
+    /// The player can move this far from the center of the screen before the camera follows.
+    pub deadzone: Vec2,
+    /// How quickly the camera catches up, as a fraction of the remaining distance per second.
+    pub smoothing: f32,
+    /// Where the camera is looking before shaking, or `None` to jump straight to the player.
+    position: Option<Vec2>,
+}
+
+impl Default for CameraFollow {
+    fn default() -> Self {
+        Self {
+            deadzone: Vec2::new(400.0, 250.0),
+            smoothing: 5.0,
+            position: None,
+        }
+    }
+}
+
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct CameraShake {
+    /// From `0.0` to `1.0`. The shake grows with its square, so small hits stay subtle.
+    pub trauma: f32,
+}
+
+/// Trigger this to shake the camera.
+/// Trauma adds up to at most `1.0` and wears off over time.
+#[derive(Event, Debug)]
+pub struct AddTrauma(pub f32);
+
+fn add_trauma(trigger: Trigger<AddTrauma>, mut cameras: Query<&mut CameraShake>) {
+    for mut shake in &mut cameras {
+        shake.trauma = (shake.trauma + trigger.event().0).clamp(0.0, 1.0);
+    }
+}
+
+/// Jump to a freshly spawned player instead of panning over from the last run.
+fn recenter_on_player(
+    _trigger: Trigger<OnAdd, Player>,
+    mut cameras: Query<(&mut CameraFollow, &mut CameraShake)>,
+) {
+    for (mut follow, mut shake) in &mut cameras {
+        follow.position = None;
+        shake.trauma = 0.0;
+    }
+}
+
+fn shake_on_player_damage(
+    trigger: Trigger<Damaged>,
+    players: Query<(), With<Player>>,
+    mut commands: Commands,
+) {
+    if players.contains(trigger.entity()) {
+        commands.trigger(AddTrauma(DAMAGE_TRAUMA));
+    }
+}
+
+fn shake_on_dash(
+    trigger: Trigger<OnAdd, Dash>,
+    players: Query<(), With<Player>>,
+    mut commands: Commands,
+) {
+    if players.contains(trigger.entity()) {
+        commands.trigger(AddTrauma(DASH_TRAUMA));
+    }
+}
+
+/// Show as much of the world as the level asks for.
+fn zoom_to_level(
+    level: Res<Level>,
+    mut cameras: Query<&mut OrthographicProjection, With<CameraFollow>>,
+) {
+    for mut projection in &mut cameras {
+        projection.scale = level.camera_scale;
+    }
+}
+
+fn follow_player(
+    time: Res<Time>,
+    level: Option<Res<Level>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    players: Query<&Transform, (With<Player>, Without<CameraFollow>)>,
+    mut cameras: Query<(&mut CameraFollow, &OrthographicProjection)>,
+) {
+    let (Ok(player_transform), Ok(window)) = (players.get_single(), windows.get_single()) else {
+        return;
+    };
+    let player = player_transform.translation.truncate();
+    for (mut follow, projection) in &mut cameras {
+        // Measured from the window, since the projection's area is only updated when rendering.
+        let half_view = window.size() * projection.scale / 2.0;
+        let position = match follow.position {
+            Some(position) => {
+                // Only chase the part of the offset that's outside the deadzone.
+                let offset = player - position;
+                let outside = offset - offset.clamp(-follow.deadzone, follow.deadzone);
+                let target = position + outside;
+                target + (position - target) * (-follow.smoothing * time.delta_seconds()).exp()
+            }
+            None => player,
+        };
+        let position = match &level {
+            Some(level) => clamp_view(position, half_view, level.bounds),
+            None => position,
+        };
+        follow.position = Some(position);
+    }
+}
+
+/// Move the center of the view so that it stays within `bounds`,
+/// or center it on them along axes where the view is bigger than they are.
+fn clamp_view(center: Vec2, half_view: Vec2, bounds: Rect) -> Vec2 {
+    let min = bounds.min + half_view;
+    let max = bounds.max - half_view;
+    Vec2::new(
+        if min.x <= max.x {
+            center.x.clamp(min.x, max.x)
+        } else {
+            bounds.center().x
+        },
+        if min.y <= max.y {
+            center.y.clamp(min.y, max.y)
+        } else {
+            bounds.center().y
+        },
+    )
+}
+
+fn shake_camera(
+    time: Res<Time>,
+    mut cameras: Query<(&CameraFollow, &mut CameraShake, &mut Transform)>,
+) {
+    let t = time.elapsed_seconds() * SHAKE_FREQUENCY;
+    for (follow, mut shake, mut transform) in &mut cameras {
+        let Some(position) = follow.position else {
+            continue;
+        };
+        let strength = shake.trauma * shake.trauma;
+        let offset = Vec2::new(noise(t, 0.0), noise(t, 10.0)) * MAX_SHAKE_OFFSET * strength;
+        transform.translation = (position + offset).extend(transform.translation.z);
+        transform.rotation = Quat::from_rotation_z(noise(t, 20.0) * MAX_SHAKE_ANGLE * strength);
+        shake.trauma = (shake.trauma - TRAUMA_DECAY * time.delta_seconds()).max(0.0);
+    }
+}
+
+/// Smooth noise from `-1.0` to `1.0`, different for each `seed`.
+/// Made from sine waves instead of [`GameRng`](super::rng::GameRng),
+/// so that shaking doesn't change what happens in the game.
+fn noise(t: f32, seed: f32) -> f32 {
+    (t + seed).sin() * 0.6 + (t * 2.3 + seed * 1.7).sin() * 0.4
+}
@@ -4,3 +4,5 @@ pub const PLAYER_GROUP: Group = Group::GROUP_1;
 pub const ENEMY_GROUP: Group = Group::GROUP_2;
 pub const HIT_BOX_GROUP: Group = Group::GROUP_3;
 pub const SOIL_GROUP: Group = Group::GROUP_4;
+/// Solid terrain that blocks bullets and line of sight.
+pub const OBSTACLE_GROUP: Group = Group::GROUP_5;
@@ -0,0 +1,199 @@
+//! The day/night cycle. Plants grow during the day,
+//! while the night belongs to hatching plants and faster enemies.
+//! Systems that care about the phase react to [`PhaseChanged`].
+
+use std::time::Duration;
+
+use bevy::{color::palettes::css::WHITE, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{screen::Screen, AppSet};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<(DayCycle, NightTint, CycleDisplay)>();
+    app.init_resource::<DayCycle>();
+    app.add_systems(
+        OnEnter(Screen::Playing),
+        (restart_day_cycle, spawn_night_tint, spawn_cycle_display),
+    );
+    app.add_systems(
+        Update,
+        (
+            tick_day_cycle.in_set(AppSet::TickTimers),
+            (update_night_tint, update_cycle_display).in_set(AppSet::Update),
+        )
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+#[derive(Reflect, Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DayPhase {
+    #[default]
+    Day,
+    Night,
+}
+
+#[derive(Resource, Reflect, Clone, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub struct DayCycle {
+    pub day_duration: Duration,
+    pub night_duration: Duration,
+    /// How much faster enemies move at night.
+    pub night_enemy_speed: f32,
+    pub phase: DayPhase,
+    /// Which day of the run it is, starting at 1.
+    pub day: u32,
+    /// Time left in the current phase.
+    pub timer: Timer,
+}
+
+impl Default for DayCycle {
+    fn default() -> Self {
+        let day_duration = Duration::from_secs(60);
+        Self {
+            day_duration,
+            night_duration: Duration::from_secs(30),
+            night_enemy_speed: 1.5,
+            phase: DayPhase::Day,
+            day: 1,
+            timer: Timer::new(day_duration, TimerMode::Once),
+        }
+    }
+}
+
+impl DayCycle {
+    /// Fraction of each phase spent fading into the next one.
+    const TWILIGHT: f32 = 0.2;
+
+    pub fn is_day(&self) -> bool {
+        self.phase == DayPhase::Day
+    }
+
+    pub fn enemy_speed_multiplier(&self) -> f32 {
+        match self.phase {
+            DayPhase::Day => 1.0,
+            DayPhase::Night => self.night_enemy_speed,
+        }
+    }
+
+    /// How dark it is, from `0.0` at noon to `1.0` at midnight.
+    pub fn darkness(&self) -> f32 {
+        let twilight =
+            ((self.timer.fraction() - (1.0 - Self::TWILIGHT)) / Self::TWILIGHT).clamp(0.0, 1.0);
+        match self.phase {
+            DayPhase::Day => twilight,
+            DayPhase::Night => 1.0 - twilight,
+        }
+    }
+
+    /// Start over at the first day, keeping the configured durations.
+    fn restart(&mut self) {
+        self.phase = DayPhase::Day;
+        self.day = 1;
+        self.timer = Timer::new(self.day_duration, TimerMode::Once);
+    }
+}
+
+/// Triggered whenever day turns to night or night turns to day.
+#[derive(Event)]
+pub struct PhaseChanged {
+    pub phase: DayPhase,
+}
+
+fn restart_day_cycle(mut day_cycle: ResMut<DayCycle>) {
+    day_cycle.restart();
+}
+
+fn tick_day_cycle(time: Res<Time>, mut day_cycle: ResMut<DayCycle>, mut commands: Commands) {
+    day_cycle.timer.tick(time.delta());
+    if !day_cycle.timer.just_finished() {
+        return;
+    }
+
+    let (phase, duration) = match day_cycle.phase {
+        DayPhase::Day => (DayPhase::Night, day_cycle.night_duration),
+        DayPhase::Night => {
+            day_cycle.day += 1;
+            (DayPhase::Day, day_cycle.day_duration)
+        }
+    };
+    day_cycle.phase = phase;
+    day_cycle.timer = Timer::new(duration, TimerMode::Once);
+    commands.trigger(PhaseChanged { phase });
+}
+
+const NIGHT_TINT: Color = Color::srgba(0.02, 0.02, 0.15, 0.5);
+
+/// A full screen overlay that darkens the world at night.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct NightTint;
+
+fn spawn_night_tint(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Night Tint"),
+        NightTint,
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            background_color: BackgroundColor(NIGHT_TINT.with_alpha(0.0)),
+            // Draw beneath the rest of the UI.
+            z_index: ZIndex::Global(-1),
+            ..default()
+        },
+        StateScoped(Screen::Playing),
+    ));
+}
+
+fn update_night_tint(
+    day_cycle: Res<DayCycle>,
+    mut tints: Query<&mut BackgroundColor, With<NightTint>>,
+) {
+    for mut background in &mut tints {
+        background.0 = NIGHT_TINT.with_alpha(NIGHT_TINT.alpha() * day_cycle.darkness());
+    }
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct CycleDisplay;
+
+fn spawn_cycle_display(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Cycle Display"),
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: default(),
+                font_size: 50.0,
+                color: WHITE.into(),
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            left: Val::Px(10.0),
+            ..default()
+        }),
+        CycleDisplay,
+        StateScoped(Screen::Playing),
+    ));
+}
+
+fn update_cycle_display(
+    day_cycle: Res<DayCycle>,
+    mut displays: Query<&mut Text, With<CycleDisplay>>,
+) {
+    let phase = match day_cycle.phase {
+        DayPhase::Day => "Day",
+        DayPhase::Night => "Night",
+    };
+    let remaining = day_cycle.timer.remaining_secs().ceil();
+    for mut text in &mut displays {
+        text.sections[0].value = format!("{phase} {} - {remaining}s", day_cycle.day);
+    }
+}
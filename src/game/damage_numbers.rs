@@ -0,0 +1,124 @@
+//! Numbers that pop up over entities when they take damage, then rise and fade away.
+
+use std::time::Duration;
+
+use bevy::{
+    color::palettes::css::{LIME, ORANGE, WHITE},
+    prelude::*,
+};
+
+use super::{
+    health::{DamageType, Damaged},
+    layers,
+};
+use crate::{screen::Screen, AppSet};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<DamageNumber>();
+    app.observe(spawn_damage_number);
+    app.add_systems(
+        Update,
+        (
+            tick_damage_numbers.in_set(AppSet::TickTimers),
+            (
+                rise_damage_numbers,
+                despawn_damage_numbers,
+                fade_damage_numbers,
+            )
+                .chain()
+                .in_set(AppSet::Update),
+        ),
+    );
+}
+
+/// How long a damage number stays on screen.
+const DAMAGE_NUMBER_DURATION: Duration = Duration::from_millis(800);
+/// How fast damage numbers rise, in pixels per second.
+const DAMAGE_NUMBER_SPEED: f32 = 300.0;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct DamageNumber {
+    starting_color: Color,
+    timer: Timer,
+}
+
+fn damage_type_color(damage_type: DamageType) -> Color {
+    match damage_type {
+        DamageType::Physical => WHITE.into(),
+        DamageType::Fire => ORANGE.into(),
+        DamageType::Poison => LIME.into(),
+    }
+}
+
+fn spawn_damage_number(
+    trigger: Trigger<Damaged>,
+    transforms: Query<&GlobalTransform>,
+    mut commands: Commands,
+) {
+    let Ok(global_transform) = transforms.get(trigger.entity()) else {
+        return;
+    };
+    let event = trigger.event();
+    let color = damage_type_color(event.damage_type);
+    // Show a whole number when there's no fraction to show.
+    let text = if event.amount.fract() == 0.0 {
+        format!("{}", event.amount)
+    } else {
+        format!("{:.1}", event.amount)
+    };
+    commands.spawn((
+        Name::new("Damage Number"),
+        DamageNumber {
+            starting_color: color,
+            timer: Timer::new(DAMAGE_NUMBER_DURATION, TimerMode::Once),
+        },
+        Text2dBundle {
+            text: Text::from_section(
+                text,
+                TextStyle {
+                    font_size: 100.0,
+                    color,
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(
+                global_transform
+                    .translation()
+                    .truncate()
+                    .extend(layers::DAMAGE_NUMBERS),
+            ),
+            ..default()
+        },
+        StateScoped(Screen::Playing),
+    ));
+}
+
+fn tick_damage_numbers(time: Res<Time>, mut numbers: Query<&mut DamageNumber>) {
+    for mut number in &mut numbers {
+        number.timer.tick(time.delta());
+    }
+}
+
+fn rise_damage_numbers(time: Res<Time>, mut numbers: Query<&mut Transform, With<DamageNumber>>) {
+    for mut transform in &mut numbers {
+        transform.translation.y += DAMAGE_NUMBER_SPEED * time.delta_seconds();
+    }
+}
+
+fn fade_damage_numbers(mut numbers: Query<(&DamageNumber, &mut Text)>) {
+    for (number, mut text) in &mut numbers {
+        let alpha = number.starting_color.alpha() * number.timer.fraction_remaining();
+        for section in &mut text.sections {
+            section.style.color = number.starting_color.with_alpha(alpha);
+        }
+    }
+}
+
+fn despawn_damage_numbers(numbers: Query<(Entity, &DamageNumber)>, mut commands: Commands) {
+    for (entity, number) in &numbers {
+        if number.timer.finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
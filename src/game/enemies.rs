@@ -1,16 +1,33 @@
+use std::time::Duration;
+
 use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use super::{health::Died, items::SpawnItem, spawn::player::Player};
+use super::{
+    collision_groups::HIT_BOX_GROUP,
+    cycle::{DayCycle, PhaseChanged},
+    health::{Damage, DamageType, Died},
+    items::{SpawnItem, DROP_SCATTER},
+    plant::Soil,
+    rng::{point_near, GameRng},
+    spatial::{spatial_grid_plugin, SpatialGrid},
+    spawn::player::Player,
+    status::StatusEffects,
+};
 
-pub(super) fn plugin(app: &mut App) {
-    app.register_type::<Enemy>();
-    app.observe(kill_enemy);
+pub fn plugin(app: &mut App) {
+    app.add_plugins(spatial_grid_plugin::<Enemy>);
+    app.register_type::<(Enemy, EnemyArchetype, KeepDistance, Burrow, Underground)>();
+    app.observe(kill_enemy)
+        .observe(match_new_enemy_to_phase)
+        .observe(match_enemies_to_phase);
     app.add_systems(
-        Update,
+        FixedUpdate,
         (
             follow_player,
-            push_enemies_away_from_each_other,
-            push_enemies_away_from_player,
+            burrow,
+            damage_player_on_contact,
             push_enemies_away_from_each_other,
             push_enemies_away_from_player,
         )
@@ -21,60 +38,273 @@ pub(super) fn plugin(app: &mut App) {
 #[derive(Component, Reflect)]
 #[reflect(Component)]
 pub struct Enemy {
+    pub archetype: EnemyArchetype,
     pub max_speed: f32,
+    /// Multiplies [`Enemy::max_speed`], set from the [`DayCycle`] whenever the phase changes.
+    pub phase_speed: f32,
+    /// Damage dealt to the player on touch.
+    pub contact_damage: f32,
+}
+
+/// The kinds of enemies, each with its own stats, looks and behavior.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Reflect, Serialize, Deserialize)]
+pub enum EnemyArchetype {
+    /// Runs straight at the player.
+    #[default]
+    Chaser,
+    /// Keeps its distance and shoots at the player.
+    Shooter,
+    /// Tunnels between soil tiles and pops up near the player.
+    Burrower,
+}
+
+/// How close an enemy's center gets to the player's before it's pushed back.
+const PLAYER_CONTACT_DISTANCE: f32 = 150.0;
+
+/// Makes an enemy hold position at a distance from the player instead of chasing them.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct KeepDistance {
+    pub distance: f32,
+}
+
+fn match_new_enemy_to_phase(
+    trigger: Trigger<OnAdd, Enemy>,
+    day_cycle: Res<DayCycle>,
+    mut enemies: Query<&mut Enemy>,
+) {
+    if let Ok(mut enemy) = enemies.get_mut(trigger.entity()) {
+        enemy.phase_speed = day_cycle.enemy_speed_multiplier();
+    }
+}
+
+/// Enemies speed up at night.
+fn match_enemies_to_phase(
+    _trigger: Trigger<PhaseChanged>,
+    day_cycle: Res<DayCycle>,
+    mut enemies: Query<&mut Enemy>,
+) {
+    let phase_speed = day_cycle.enemy_speed_multiplier();
+    for mut enemy in &mut enemies {
+        enemy.phase_speed = phase_speed;
+    }
 }
 
 fn follow_player(
     time: Res<Time>,
-    mut enemies: Query<(&Enemy, &mut Transform)>,
-    players: Query<&GlobalTransform, With<Player>>,
+    mut enemies: Query<
+        (
+            &Enemy,
+            Option<&KeepDistance>,
+            Option<&StatusEffects>,
+            &mut Transform,
+        ),
+        (Without<Burrow>, Without<Player>),
+    >,
+    players: Query<&Transform, With<Player>>,
 ) {
     if let Ok(player) = players.get_single() {
-        let player_position = player.translation().truncate();
-        for (enemy, mut transform) in &mut enemies {
+        let player_position = player.translation.truncate();
+        for (enemy, keep_distance, status_effects, mut transform) in &mut enemies {
             if let Ok((direction, length)) =
                 Dir2::new_and_length(player_position - transform.translation.truncate())
             {
-                let displacement = direction * (enemy.max_speed * time.delta_seconds()).min(length);
+                // Back away from a player that gets too close.
+                let (direction, length) = match keep_distance {
+                    Some(keep_distance) if length < keep_distance.distance => {
+                        (-direction, keep_distance.distance - length)
+                    }
+                    Some(keep_distance) => (direction, length - keep_distance.distance),
+                    None => (direction, length),
+                };
+                let speed = enemy.max_speed
+                    * enemy.phase_speed
+                    * status_effects.map_or(1.0, StatusEffects::speed_multiplier);
+                let displacement = direction * (speed * time.delta_seconds()).min(length);
                 transform.translation += displacement.extend(0.0);
             }
         }
     }
 }
 
+/// Moves an enemy underground from one soil tile to the one nearest the player.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Burrow {
+    /// How long the enemy stays above ground after popping up.
+    pub surface_time: Duration,
+    pub state: BurrowState,
+}
+
+#[derive(Reflect)]
+pub enum BurrowState {
+    Surfaced { timer: Timer },
+    Tunneling { target: Vec2 },
+}
+
+/// Marks enemies that are underground, where they can't hurt or be hurt.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Underground;
+
+fn burrow(
+    time: Res<Time>,
+    mut enemies: Query<
+        (
+            Entity,
+            &Enemy,
+            Option<&StatusEffects>,
+            &mut Burrow,
+            &mut Transform,
+            &mut Visibility,
+            &mut CollisionGroups,
+        ),
+        Without<Player>,
+    >,
+    players: Query<&Transform, With<Player>>,
+    soil: Query<&GlobalTransform, With<Soil>>,
+    mut commands: Commands,
+) {
+    let Ok(player) = players.get_single() else {
+        return;
+    };
+    let player_position = player.translation.truncate();
+
+    for (
+        entity,
+        enemy,
+        status_effects,
+        mut burrow,
+        mut transform,
+        mut visibility,
+        mut collision_groups,
+    ) in &mut enemies
+    {
+        let surface_time = burrow.surface_time;
+        let position = transform.translation.truncate();
+        match &mut burrow.state {
+            BurrowState::Surfaced { timer } => {
+                timer.tick(time.delta());
+                if !timer.finished() {
+                    continue;
+                }
+                // Dig towards the tile closest to the player.
+                let Some(target) = soil
+                    .iter()
+                    .map(|soil| soil.translation().truncate())
+                    .filter(|tile| tile.distance(position) > 1.0)
+                    .min_by(|a, b| {
+                        a.distance_squared(player_position)
+                            .total_cmp(&b.distance_squared(player_position))
+                    })
+                else {
+                    continue;
+                };
+                burrow.state = BurrowState::Tunneling { target };
+                *visibility = Visibility::Hidden;
+                collision_groups.filters = Group::NONE;
+                commands.entity(entity).insert(Underground);
+            }
+            BurrowState::Tunneling { target } => {
+                let speed = enemy.max_speed
+                    * enemy.phase_speed
+                    * status_effects.map_or(1.0, StatusEffects::speed_multiplier);
+                let offset = *target - position;
+                let distance = speed * time.delta_seconds();
+                if offset.length() > distance {
+                    transform.translation += (offset.normalize() * distance).extend(0.0);
+                    continue;
+                }
+                transform.translation = target.extend(transform.translation.z);
+                burrow.state = BurrowState::Surfaced {
+                    timer: Timer::new(surface_time, TimerMode::Once),
+                };
+                *visibility = Visibility::Inherited;
+                collision_groups.filters = HIT_BOX_GROUP;
+                commands.entity(entity).remove::<Underground>();
+            }
+        }
+    }
+}
+
+fn damage_player_on_contact(
+    enemies: Query<(Entity, &Enemy, &Transform), Without<Underground>>,
+    players: Query<(Entity, &Transform), With<Player>>,
+    mut commands: Commands,
+) {
+    let Ok((player, player_transform)) = players.get_single() else {
+        return;
+    };
+
+    let player_position = player_transform.translation.truncate();
+    for (entity, enemy, transform) in &enemies {
+        if player_position.distance(transform.translation.truncate()) <= PLAYER_CONTACT_DISTANCE {
+            commands.trigger_targets(
+                Damage {
+                    amount: enemy.contact_damage,
+                    damage_type: DamageType::Physical,
+                    source: Some(entity),
+                },
+                player,
+            );
+        }
+    }
+}
+
 fn kill_enemy(
     trigger: Trigger<Died>,
-    enemies: Query<&GlobalTransform, With<Enemy>>,
+    enemies: Query<&Transform, With<Enemy>>,
+    mut rng: ResMut<GameRng>,
     mut commands: Commands,
 ) {
     if let Ok(transform) = enemies.get(trigger.entity()) {
         commands.trigger(SpawnItem {
-            position: transform.translation().truncate(),
+            position: point_near(
+                rng.gameplay(),
+                transform.translation.truncate(),
+                DROP_SCATTER,
+            ),
         });
 
         commands.entity(trigger.entity()).despawn_recursive();
     }
 }
 
-fn push_enemies_away_from_each_other(mut enemies: Query<&mut Transform, With<Enemy>>) {
-    let mut combinations = enemies.iter_combinations_mut();
-    while let Some([mut a, mut b]) = combinations.fetch_next() {
-        let (direction, distance) =
-            Dir2::new_and_length(a.translation.truncate() - b.translation.truncate())
-                .unwrap_or((Dir2::NORTH, 0.0));
+/// How far apart enemies' centers are kept.
+const ENEMY_SEPARATION: f32 = 100.0;
 
-        let overlap = 100.0 - distance;
-        if overlap > 0.0 {
-            let delta = (direction * (overlap / 2.0)).extend(0.0);
-            a.translation += delta;
-            b.translation -= delta;
+fn push_enemies_away_from_each_other(
+    grid: Res<SpatialGrid<Enemy>>,
+    mut enemies: Query<&mut Transform, (With<Enemy>, Without<Underground>)>,
+) {
+    for (a, position) in grid.iter() {
+        // Enemies may have moved since the grid was built, so search a little further.
+        for (b, _) in grid.query(position, ENEMY_SEPARATION * 2.0) {
+            // Visit each pair once.
+            if b <= a {
+                continue;
+            }
+            let Ok([mut a, mut b]) = enemies.get_many_mut([a, b]) else {
+                continue;
+            };
+
+            let (direction, distance) =
+                Dir2::new_and_length(a.translation.truncate() - b.translation.truncate())
+                    .unwrap_or((Dir2::NORTH, 0.0));
+
+            let overlap = ENEMY_SEPARATION - distance;
+            if overlap > 0.0 {
+                let delta = (direction * (overlap / 2.0)).extend(0.0);
+                a.translation += delta;
+                b.translation -= delta;
+            }
         }
     }
 }
 
 fn push_enemies_away_from_player(
-    mut enemies: Query<&mut Transform, With<Enemy>>,
-    players: Query<&GlobalTransform, With<Player>>,
+    mut enemies: Query<&mut Transform, (With<Enemy>, Without<Underground>, Without<Player>)>,
+    players: Query<&Transform, With<Player>>,
 ) {
     let Ok(player) = players.get_single() else {
         return;
@@ -82,10 +312,10 @@ fn push_enemies_away_from_player(
 
     for mut enemy in &mut enemies {
         let (direction, distance) =
-            Dir2::new_and_length(player.translation().truncate() - enemy.translation.truncate())
+            Dir2::new_and_length(player.translation.truncate() - enemy.translation.truncate())
                 .unwrap_or((Dir2::NORTH, 0.0));
 
-        let overlap = 150.0 - distance;
+        let overlap = PLAYER_CONTACT_DISTANCE - distance;
         if overlap > 0.0 {
             let delta = (direction * overlap).extend(0.0);
             enemy.translation -= delta;
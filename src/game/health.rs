@@ -1,40 +1,149 @@
-use bevy::prelude::*;
+use std::time::Duration;
+
+use bevy::{prelude::*, utils::HashMap};
+use serde::{Deserialize, Serialize};
+
+use crate::AppSet;
 
 pub(crate) fn plugin(app: &mut App) {
-    app.register_type::<Health>();
+    app.register_type::<(Health, Invulnerability)>();
     app.observe(damage);
+    app.add_systems(
+        Update,
+        (
+            tick_invulnerability.in_set(AppSet::TickTimers),
+            blink_invulnerable.in_set(AppSet::Update),
+        ),
+    );
 }
 
-#[derive(Component, Reflect)]
+/// What kind of harm [`Damage`] does, so entities can resist some kinds more than others.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, Reflect, Serialize, Deserialize)]
+pub enum DamageType {
+    #[default]
+    Physical,
+    Fire,
+    Poison,
+}
+
+#[derive(Component, Reflect, Clone, Debug, Serialize, Deserialize)]
 #[reflect(Component)]
 pub struct Health {
     pub max: f32,
     pub current: f32,
+    /// The fraction of each damage type that is ignored.
+    /// Negative values make the entity weak to that type instead.
+    pub resistances: HashMap<DamageType, f32>,
 }
 
 impl Health {
     pub fn full(max: f32) -> Self {
-        Self { max, current: max }
+        Self {
+            max,
+            current: max,
+            resistances: HashMap::default(),
+        }
+    }
+
+    pub fn with_resistance(mut self, damage_type: DamageType, resistance: f32) -> Self {
+        self.resistances.insert(damage_type, resistance);
+        self
+    }
+
+    /// How much of `amount` gets through this entity's resistance to the damage type.
+    pub fn resisted(&self, amount: f32, damage_type: DamageType) -> f32 {
+        let resistance = self.resistances.get(&damage_type).copied().unwrap_or(0.0);
+        amount * (1.0 - resistance.min(1.0))
+    }
+}
+
+/// Makes an entity ignore [`Damage`] for a while after each hit.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Invulnerability {
+    timer: Timer,
+}
+
+impl Invulnerability {
+    pub fn new(duration: Duration) -> Self {
+        let mut timer = Timer::new(duration, TimerMode::Once);
+        timer.tick(duration);
+        Self { timer }
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.timer.finished()
     }
 }
 
 #[derive(Event)]
 pub struct Damage {
     pub amount: f32,
+    pub damage_type: DamageType,
+    /// The entity that caused the damage, like the shooter of a bullet.
+    pub source: Option<Entity>,
+}
+
+/// Triggered on an entity after [`Damage`] got through its invulnerability and resistances.
+#[derive(Event)]
+pub struct Damaged {
+    pub amount: f32,
+    pub damage_type: DamageType,
+    pub source: Option<Entity>,
 }
 
 #[derive(Event)]
-pub struct Died;
+pub struct Died {
+    /// The source of the damage that killed the entity, to give credit for the kill.
+    pub killer: Option<Entity>,
+}
 
 fn damage(
     trigger: Trigger<Damage>,
-    mut health: Query<(Entity, &mut Health)>,
+    mut health: Query<(Entity, &mut Health, Option<&mut Invulnerability>)>,
     mut commands: Commands,
 ) {
-    if let Ok((entity, mut health)) = health.get_mut(trigger.entity()) {
-        health.current -= trigger.event().amount;
+    if let Ok((entity, mut health, invulnerability)) = health.get_mut(trigger.entity()) {
+        if let Some(mut invulnerability) = invulnerability {
+            if invulnerability.is_active() {
+                return;
+            }
+            invulnerability.timer.reset();
+        }
+
+        let event = trigger.event();
+        let amount = health.resisted(event.amount, event.damage_type);
+        health.current -= amount;
+        commands.trigger_targets(
+            Damaged {
+                amount,
+                damage_type: event.damage_type,
+                source: event.source,
+            },
+            entity,
+        );
         if health.current <= 0.0 {
-            commands.trigger_targets(Died, entity);
+            commands.trigger_targets(
+                Died {
+                    killer: event.source,
+                },
+                entity,
+            );
         }
     }
 }
+
+fn tick_invulnerability(time: Res<Time>, mut query: Query<&mut Invulnerability>) {
+    for mut invulnerability in &mut query {
+        invulnerability.timer.tick(time.delta());
+    }
+}
+
+/// Flicker invulnerable entities so it's clear they can't be hurt.
+fn blink_invulnerable(mut query: Query<(&Invulnerability, &mut Sprite)>) {
+    for (invulnerability, mut sprite) in &mut query {
+        let visible = !invulnerability.is_active()
+            || (invulnerability.timer.elapsed_secs() * 10.0) as u32 % 2 == 1;
+        sprite.color.set_alpha(if visible { 1.0 } else { 0.3 });
+    }
+}
@@ -0,0 +1,141 @@
+//! Health bars over damaged entities.
+//! Every entity with [`Health`] and a sprite gets a bar as a child,
+//! which shows while it's hurt and [`Settings::health_bars`] is on.
+
+use bevy::{
+    color::palettes::css::{DARK_SLATE_GRAY, LIME, RED},
+    prelude::*,
+    sprite::Anchor,
+};
+
+use super::{health::Health, layers};
+use crate::{settings::Settings, AppSet};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<(HasHealthBar, HealthBarFill)>();
+    app.add_systems(
+        Update,
+        (spawn_health_bars, update_health_bars)
+            .chain()
+            .in_set(AppSet::Update),
+    );
+}
+
+/// The size of a health bar, in pixels.
+const HEALTH_BAR_SIZE: Vec2 = Vec2::new(120.0, 16.0);
+/// The gap between the top of the sprite and the health bar, in pixels.
+const HEALTH_BAR_GAP: f32 = 20.0;
+
+/// Points to the health bar of an entity.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct HasHealthBar(Entity);
+
+/// The part of a health bar that shrinks as health is lost.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct HealthBarFill;
+
+fn spawn_health_bars(
+    owners: Query<Entity, (With<Health>, With<Sprite>, Without<HasHealthBar>)>,
+    mut commands: Commands,
+) {
+    for owner in &owners {
+        let bar = commands
+            .spawn((
+                Name::new("Health Bar"),
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: DARK_SLATE_GRAY.into(),
+                        custom_size: Some(Vec2::ONE),
+                        ..default()
+                    },
+                    visibility: Visibility::Hidden,
+                    ..default()
+                },
+            ))
+            .with_children(|children| {
+                children.spawn((
+                    Name::new("Health Bar Fill"),
+                    HealthBarFill,
+                    SpriteBundle {
+                        sprite: Sprite {
+                            custom_size: Some(Vec2::ONE),
+                            anchor: Anchor::CenterLeft,
+                            ..default()
+                        },
+                        transform: Transform::from_xyz(-0.5, 0.0, 0.01),
+                        ..default()
+                    },
+                ));
+            })
+            .set_parent(owner)
+            .id();
+        commands.entity(owner).insert(HasHealthBar(bar));
+    }
+}
+
+fn update_health_bars(
+    settings: Res<Settings>,
+    texture_atlas_layouts: Option<Res<Assets<TextureAtlasLayout>>>,
+    owners: Query<(
+        Entity,
+        &Health,
+        &Transform,
+        &Sprite,
+        Option<&TextureAtlas>,
+        &HasHealthBar,
+    )>,
+    mut bars: Query<
+        (&mut Transform, &mut Visibility, &Children),
+        (Without<HasHealthBar>, Without<HealthBarFill>),
+    >,
+    mut fills: Query<(&mut Transform, &mut Sprite), (With<HealthBarFill>, Without<HasHealthBar>)>,
+    mut commands: Commands,
+) {
+    for (owner, health, owner_transform, owner_sprite, texture_atlas, has_health_bar) in &owners {
+        let Ok((mut transform, mut visibility, children)) = bars.get_mut(has_health_bar.0) else {
+            // Something despawned the bar along with the owner's other children,
+            // so let it get a new one.
+            commands.entity(owner).remove::<HasHealthBar>();
+            continue;
+        };
+
+        let fraction = (health.current / health.max).clamp(0.0, 1.0);
+        let shown = settings.health_bars && fraction < 1.0;
+        *visibility = if shown {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        if !shown {
+            continue;
+        }
+
+        // The bar is a child, so undo the owner's scale to keep its size in pixels.
+        let owner_scale = owner_transform.scale;
+        let sprite_height = owner_sprite
+            .custom_size
+            .map(|size| size.y)
+            .or_else(|| {
+                let texture_atlas = texture_atlas?;
+                let layout = texture_atlas_layouts.as_ref()?.get(&texture_atlas.layout)?;
+                Some(layout.textures.get(texture_atlas.index)?.height() as f32)
+            })
+            .unwrap_or(1.0);
+        let top = sprite_height / 2.0 * owner_scale.y;
+        transform.translation = Vec3::new(
+            0.0,
+            (top + HEALTH_BAR_GAP + HEALTH_BAR_SIZE.y / 2.0) / owner_scale.y,
+            (layers::HEALTH_BARS - owner_transform.translation.z) / owner_scale.z,
+        );
+        transform.scale = (HEALTH_BAR_SIZE / owner_scale.truncate()).extend(1.0);
+
+        for &child in children {
+            if let Ok((mut fill_transform, mut fill_sprite)) = fills.get_mut(child) {
+                fill_transform.scale.x = fraction;
+                fill_sprite.color = Color::from(RED).mix(&LIME.into(), fraction);
+            }
+        }
+    }
+}
@@ -0,0 +1,415 @@
+//! Map keyboard and gamepad input to the actions the player can take.
+//! Systems read the [`ActionState`] instead of raw input, so bindings can be changed in one place.
+
+use bevy::{
+    input::InputSystem,
+    prelude::*,
+    utils::{HashMap, HashSet},
+    window::PrimaryWindow,
+};
+use serde::{Deserialize, Serialize};
+
+pub fn plugin(app: &mut App) {
+    app.register_type::<ActionMap>();
+    app.init_resource::<ActionMap>();
+    app.init_resource::<ActionState>();
+    app.add_systems(
+        PreUpdate,
+        record_actions.in_set(ActionSystem).after(InputSystem),
+    );
+}
+
+/// Records the [`ActionState`] in the `PreUpdate` schedule.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ActionSystem;
+
+/// Something the player can do.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+pub enum Action {
+    /// A direction, see [`ActionState::axis`].
+    Move,
+    /// A direction to aim in, see [`ActionState::axis`].
+    Aim,
+    Dash,
+    /// Equip the tool at this index in [`ToolKind::ALL`](super::tools::ToolKind::ALL).
+    SelectTool(usize),
+    NextTool,
+    NextSeed,
+    /// Apply the current tool to the soil it's touching.
+    Use,
+    Shop,
+    Pause,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub enum InputButton {
+    Key(KeyCode),
+    Gamepad(GamepadButtonType),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub enum GamepadStick {
+    Left,
+    Right,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub enum Binding {
+    Button(InputButton),
+    /// Four buttons acting as a direction.
+    Buttons {
+        up: InputButton,
+        down: InputButton,
+        left: InputButton,
+        right: InputButton,
+    },
+    Stick(GamepadStick),
+}
+
+/// Which inputs trigger each action.
+#[derive(Resource, Reflect, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub struct ActionMap {
+    pub bindings: HashMap<Action, Vec<Binding>>,
+}
+
+impl Default for ActionMap {
+    fn default() -> Self {
+        use GamepadButtonType as Pad;
+        use InputButton::{Gamepad, Key};
+
+        let key = |key_code| Binding::Button(Key(key_code));
+        let pad = |button| Binding::Button(Gamepad(button));
+        Self {
+            bindings: [
+                (
+                    Action::Move,
+                    vec![
+                        Binding::Buttons {
+                            up: Key(KeyCode::KeyW),
+                            down: Key(KeyCode::KeyS),
+                            left: Key(KeyCode::KeyA),
+                            right: Key(KeyCode::KeyD),
+                        },
+                        Binding::Buttons {
+                            up: Key(KeyCode::ArrowUp),
+                            down: Key(KeyCode::ArrowDown),
+                            left: Key(KeyCode::ArrowLeft),
+                            right: Key(KeyCode::ArrowRight),
+                        },
+                        Binding::Buttons {
+                            up: Gamepad(Pad::DPadUp),
+                            down: Gamepad(Pad::DPadDown),
+                            left: Gamepad(Pad::DPadLeft),
+                            right: Gamepad(Pad::DPadRight),
+                        },
+                        Binding::Stick(GamepadStick::Left),
+                    ],
+                ),
+                (Action::Aim, vec![Binding::Stick(GamepadStick::Right)]),
+                (Action::Dash, vec![key(KeyCode::Space), pad(Pad::South)]),
+                (Action::SelectTool(0), vec![key(KeyCode::Digit1)]),
+                (Action::SelectTool(1), vec![key(KeyCode::Digit2)]),
+                (Action::SelectTool(2), vec![key(KeyCode::Digit3)]),
+                (Action::SelectTool(3), vec![key(KeyCode::Digit4)]),
+                (Action::SelectTool(4), vec![key(KeyCode::Digit5)]),
+                (Action::SelectTool(5), vec![key(KeyCode::Digit6)]),
+                (Action::SelectTool(6), vec![key(KeyCode::Digit7)]),
+                (
+                    Action::NextTool,
+                    vec![key(KeyCode::Tab), pad(Pad::RightTrigger)],
+                ),
+                (
+                    Action::NextSeed,
+                    vec![key(KeyCode::KeyQ), pad(Pad::LeftTrigger)],
+                ),
+                (Action::Use, vec![key(KeyCode::KeyE), pad(Pad::West)]),
+                (Action::Shop, vec![key(KeyCode::KeyB), pad(Pad::North)]),
+                (Action::Pause, vec![key(KeyCode::Escape), pad(Pad::Start)]),
+            ]
+            .into(),
+        }
+    }
+}
+
+/// One of the four directions of a directional action.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+pub enum MoveDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// A keyboard key in the [`ActionMap`] that can be rebound.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+pub enum KeySlot {
+    Action(Action),
+    Direction(Action, MoveDirection),
+}
+
+impl ActionMap {
+    /// Bind actions that have no entry yet to their default inputs,
+    /// e.g. actions added after the map was saved. Actions that were unbound on purpose stay unbound.
+    pub fn add_missing_defaults(&mut self) {
+        for (action, bindings) in ActionMap::default().bindings {
+            self.bindings.entry(action).or_insert(bindings);
+        }
+    }
+
+    /// The first keyboard key bound to the slot.
+    pub fn key(&self, slot: KeySlot) -> Option<KeyCode> {
+        let (action, direction) = match slot {
+            KeySlot::Action(action) => (action, None),
+            KeySlot::Direction(action, direction) => (action, Some(direction)),
+        };
+        self.bindings
+            .get(&action)?
+            .iter()
+            .find_map(|binding| match (*binding, direction) {
+                (Binding::Button(InputButton::Key(key_code)), None) => Some(key_code),
+                (buttons @ Binding::Buttons { .. }, Some(direction)) => {
+                    match direction_button(buttons, direction) {
+                        Some(InputButton::Key(key_code)) => Some(key_code),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            })
+    }
+
+    /// Replace the first keyboard key bound to the slot, or add one if there are none.
+    pub fn rebind_key(&mut self, slot: KeySlot, key_code: KeyCode) {
+        match slot {
+            KeySlot::Action(action) => {
+                let bindings = self.bindings.entry(action).or_default();
+                let existing = bindings
+                    .iter_mut()
+                    .find(|binding| matches!(binding, Binding::Button(InputButton::Key(_))));
+                match existing {
+                    Some(binding) => *binding = Binding::Button(InputButton::Key(key_code)),
+                    None => bindings.push(Binding::Button(InputButton::Key(key_code))),
+                }
+            }
+            KeySlot::Direction(action, direction) => {
+                let Some(bindings) = self.bindings.get_mut(&action) else {
+                    return;
+                };
+                for binding in bindings {
+                    let Binding::Buttons {
+                        up,
+                        down,
+                        left,
+                        right,
+                    } = binding
+                    else {
+                        continue;
+                    };
+                    let button = match direction {
+                        MoveDirection::Up => up,
+                        MoveDirection::Down => down,
+                        MoveDirection::Left => left,
+                        MoveDirection::Right => right,
+                    };
+                    if matches!(button, InputButton::Key(_)) {
+                        *button = InputButton::Key(key_code);
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn direction_button(binding: Binding, direction: MoveDirection) -> Option<InputButton> {
+    let Binding::Buttons {
+        up,
+        down,
+        left,
+        right,
+    } = binding
+    else {
+        return None;
+    };
+    Some(match direction {
+        MoveDirection::Up => up,
+        MoveDirection::Down => down,
+        MoveDirection::Left => left,
+        MoveDirection::Right => right,
+    })
+}
+
+/// The actions the player is taking this frame.
+#[derive(Resource, Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionState {
+    pressed: HashSet<Action>,
+    just_pressed: HashSet<Action>,
+    axes: HashMap<Action, Vec2>,
+    cursor: Option<Vec2>,
+}
+
+impl ActionState {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed.contains(&action)
+    }
+
+    /// The direction of a directional action, with a length of at most `1.0`.
+    /// Analog sticks keep their magnitude, so a half tilted stick gives half the length.
+    pub fn axis(&self, action: Action) -> Vec2 {
+        self.axes.get(&action).copied().unwrap_or_default()
+    }
+
+    /// Where the cursor is in the world, if it's over the window.
+    pub fn cursor(&self) -> Option<Vec2> {
+        self.cursor
+    }
+
+    /// Forget every action this frame except `keep`, so a menu can block gameplay input.
+    pub fn keep_only(&mut self, keep: Action) {
+        self.pressed.retain(|&action| action == keep);
+        self.just_pressed.retain(|&action| action == keep);
+        self.axes.retain(|&action, _| action == keep);
+    }
+}
+
+/// A run condition that is `true` on the frame the action is pressed.
+pub fn action_just_pressed(action: Action) -> impl Fn(Res<ActionState>) -> bool + Clone {
+    move |actions: Res<ActionState>| actions.just_pressed(action)
+}
+
+/// Raw input, bundled up for reading bindings.
+struct Inputs<'a> {
+    keys: &'a ButtonInput<KeyCode>,
+    gamepads: &'a Gamepads,
+    gamepad_buttons: &'a ButtonInput<GamepadButton>,
+    gamepad_axes: &'a Axis<GamepadAxis>,
+}
+
+impl Inputs<'_> {
+    fn pressed(&self, button: InputButton) -> bool {
+        match button {
+            InputButton::Key(key_code) => self.keys.pressed(key_code),
+            InputButton::Gamepad(button_type) => self.gamepads.iter().any(|gamepad| {
+                self.gamepad_buttons
+                    .pressed(GamepadButton::new(gamepad, button_type))
+            }),
+        }
+    }
+
+    fn just_pressed(&self, button: InputButton) -> bool {
+        match button {
+            InputButton::Key(key_code) => self.keys.just_pressed(key_code),
+            InputButton::Gamepad(button_type) => self.gamepads.iter().any(|gamepad| {
+                self.gamepad_buttons
+                    .just_pressed(GamepadButton::new(gamepad, button_type))
+            }),
+        }
+    }
+
+    fn stick(&self, stick: GamepadStick) -> Vec2 {
+        let (x, y) = match stick {
+            GamepadStick::Left => (GamepadAxisType::LeftStickX, GamepadAxisType::LeftStickY),
+            GamepadStick::Right => (GamepadAxisType::RightStickX, GamepadAxisType::RightStickY),
+        };
+        self.gamepads
+            .iter()
+            .map(|gamepad| {
+                Vec2::new(
+                    self.gamepad_axes
+                        .get(GamepadAxis::new(gamepad, x))
+                        .unwrap_or_default(),
+                    self.gamepad_axes
+                        .get(GamepadAxis::new(gamepad, y))
+                        .unwrap_or_default(),
+                )
+            })
+            .sum()
+    }
+
+    fn axis(&self, binding: Binding) -> Vec2 {
+        match binding {
+            Binding::Button(_) => Vec2::ZERO,
+            Binding::Buttons {
+                up,
+                down,
+                left,
+                right,
+            } => {
+                let mut direction = Vec2::ZERO;
+                if self.pressed(up) {
+                    direction.y += 1.0;
+                }
+                if self.pressed(down) {
+                    direction.y -= 1.0;
+                }
+                if self.pressed(left) {
+                    direction.x -= 1.0;
+                }
+                if self.pressed(right) {
+                    direction.x += 1.0;
+                }
+                // Normalize so that diagonal movement has the same speed as
+                // horizontal and vertical movement.
+                direction.normalize_or_zero()
+            }
+            Binding::Stick(stick) => self.stick(stick),
+        }
+    }
+}
+
+pub(super) fn record_actions(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
+    action_map: Res<ActionMap>,
+    mut action_state: ResMut<ActionState>,
+) {
+    let inputs = Inputs {
+        keys: &keys,
+        gamepads: &gamepads,
+        gamepad_buttons: &gamepad_buttons,
+        gamepad_axes: &gamepad_axes,
+    };
+
+    action_state.pressed.clear();
+    action_state.just_pressed.clear();
+    action_state.axes.clear();
+    for (&action, bindings) in &action_map.bindings {
+        let mut axis = Vec2::ZERO;
+        for &binding in bindings {
+            match binding {
+                Binding::Button(button) => {
+                    if inputs.pressed(button) {
+                        action_state.pressed.insert(action);
+                    }
+                    if inputs.just_pressed(button) {
+                        action_state.just_pressed.insert(action);
+                    }
+                }
+                Binding::Buttons { .. } | Binding::Stick(_) => axis += inputs.axis(binding),
+            }
+        }
+        if axis != Vec2::ZERO {
+            action_state.axes.insert(action, axis.clamp_length_max(1.0));
+        }
+    }
+    action_state.cursor = cursor_world_position(&windows, &cameras);
+}
+
+/// Convert the cursor's window position to world space.
+/// The camera's projection is scaled, so the two don't line up one to one.
+fn cursor_world_position(
+    windows: &Query<&Window, With<PrimaryWindow>>,
+    cameras: &Query<(&Camera, &GlobalTransform), With<Camera2d>>,
+) -> Option<Vec2> {
+    let cursor = windows.get_single().ok()?.cursor_position()?;
+    let (camera, camera_transform) = cameras.get_single().ok()?;
+    camera.viewport_to_world_2d(camera_transform, cursor)
+}
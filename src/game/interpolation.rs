@@ -0,0 +1,74 @@
+//! Smooth out movement that happens in fixed timesteps.
+//! The simulation moves entities in `FixedUpdate`, which may run zero or several times a frame,
+//! so entities with [`InterpolateTranslation`] are drawn between their last two simulated positions.
+
+use bevy::{prelude::*, transform::TransformSystem};
+
+pub fn plugin(app: &mut App) {
+    app.register_type::<InterpolateTranslation>();
+    app.add_systems(FixedFirst, restore_simulated_translation);
+    app.add_systems(FixedLast, record_simulated_translation);
+    app.add_systems(
+        PostUpdate,
+        interpolate_translation.before(TransformSystem::TransformPropagate),
+    );
+}
+
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct InterpolateTranslation {
+    /// The translation at the start and end of the last fixed step, or `None` before the first one.
+    steps: Option<(Vec3, Vec3)>,
+    /// The translation drawn last frame, to tell when something outside the simulation moved the entity.
+    shown: Option<Vec3>,
+}
+
+impl InterpolateTranslation {
+    /// Jump straight to the new translation instead of sliding there,
+    /// e.g. after wrapping around the screen.
+    pub fn teleport(&mut self) {
+        self.steps = None;
+    }
+
+    /// The translation at the end of the last fixed step.
+    pub fn simulated(&self) -> Option<Vec3> {
+        self.steps.map(|(_, end)| end)
+    }
+}
+
+fn restore_simulated_translation(
+    mut entities: Query<(&mut Transform, &mut InterpolateTranslation)>,
+) {
+    for (mut transform, mut interpolation) in &mut entities {
+        // Pick up where the last step ended, unless the entity was moved since it was drawn.
+        if let (Some((_, end)), Some(shown)) = (interpolation.steps, interpolation.shown) {
+            if shown == transform.translation {
+                transform.translation = end;
+            }
+        }
+        interpolation.steps = Some((transform.translation, transform.translation));
+    }
+}
+
+fn record_simulated_translation(mut entities: Query<(&Transform, &mut InterpolateTranslation)>) {
+    for (transform, mut interpolation) in &mut entities {
+        let start = interpolation
+            .steps
+            .map_or(transform.translation, |(start, _)| start);
+        interpolation.steps = Some((start, transform.translation));
+    }
+}
+
+pub(super) fn interpolate_translation(
+    time: Res<Time<Fixed>>,
+    mut entities: Query<(&mut Transform, &mut InterpolateTranslation)>,
+) {
+    let fraction = time.overstep_fraction();
+    for (mut transform, mut interpolation) in &mut entities {
+        let Some((start, end)) = interpolation.steps else {
+            continue;
+        };
+        transform.translation = start.lerp(end, fraction);
+        interpolation.shown = Some(transform.translation);
+    }
+}
@@ -1,32 +1,49 @@
+use std::time::Duration;
+
 use bevy::a11y::accesskit::TextSelection;
-use bevy::color::palettes::css::WHITE;
+use bevy::color::palettes::css::{RED, WHITE};
+use bevy::utils::HashMap;
 use bevy::{color::palettes::css::YELLOW, prelude::*};
 
+use serde::{Deserialize, Serialize};
+
 use crate::screen::Screen;
 use crate::ui::prelude::*;
 
-use super::spawn::player::Player;
+use super::{
+    assets::{HandleMap, PlantKey},
+    interpolation::InterpolateTranslation,
+    spatial::{spatial_grid_plugin, SpatialGrid},
+    spawn::player::Player,
+    species::PlantSpecies,
+};
 
 pub(super) fn plugin(app: &mut App) {
-    app.register_type::<(Item, DrawToward, Wallet)>();
+    app.add_plugins(spatial_grid_plugin::<Item>);
+    app.register_type::<(Item, DrawToward, Wallet, SeedInventory, SeedDisplay)>();
     app.init_resource::<Wallet>();
+    app.init_resource::<SeedInventory>();
     app.observe(spawn_item);
+    app.observe(flash_seed_display);
     app.add_systems(
-        Update,
-        (
-            draw_items_to_player,
-            draw_toward,
-            player_collects_items,
-            update_wallet_display,
-        )
-            .chain(),
+        FixedUpdate,
+        (draw_items_to_player, draw_toward, player_collects_items).chain(),
     );
+    app.add_systems(Update, (update_wallet_display, update_seed_display));
     app.add_systems(
         OnEnter(Screen::Playing),
-        (reset_wallet, setup_wallet_display),
+        (
+            reset_wallet,
+            setup_wallet_display,
+            reset_seed_inventory,
+            setup_seed_display,
+        ),
     );
 }
 
+/// How far from where it was dropped an item can land.
+pub const DROP_SCATTER: f32 = 100.0;
+
 #[derive(Event)]
 pub struct SpawnItem {
     pub position: Vec2,
@@ -49,6 +66,7 @@ fn spawn_item(trigger: Trigger<SpawnItem>, mut commands: Commands) {
             },
             ..default()
         },
+        InterpolateTranslation::default(),
         StateScoped(Screen::Playing),
     ));
 }
@@ -61,21 +79,24 @@ pub struct DrawToward {
     acceleration: f32,
 }
 
+/// How close the player needs to be for items to fly towards them.
+const ITEM_DRAW_RADIUS: f32 = 1000.0;
+/// How close an item needs to be for the player to pick it up.
+const ITEM_PICKUP_RADIUS: f32 = 50.0;
+
 fn draw_items_to_player(
-    players: Query<(Entity, &GlobalTransform), With<Player>>,
-    mut items: Query<(Entity, &GlobalTransform), (With<Item>, Without<DrawToward>)>,
+    grid: Res<SpatialGrid<Item>>,
+    players: Query<(Entity, &Transform), With<Player>>,
+    items: Query<(), (With<Item>, Without<DrawToward>)>,
     mut commands: Commands,
 ) {
     let Ok((player, player_transform)) = players.get_single() else {
         return;
     };
 
-    for (item, transform) in &mut items {
-        if (player_transform.translation() - transform.translation())
-            .truncate()
-            .length()
-            <= 1000.0
-        {
+    let player_position = player_transform.translation.truncate();
+    for (item, _) in grid.query(player_position, ITEM_DRAW_RADIUS) {
+        if items.contains(item) {
             commands.entity(item).insert(DrawToward {
                 target: player,
                 speed: 0.0,
@@ -88,16 +109,16 @@ fn draw_items_to_player(
 fn draw_toward(
     time: Res<Time>,
     mut entities: Query<(&mut DrawToward, &mut Transform)>,
-    global_transforms: Query<&GlobalTransform>,
+    targets: Query<&Transform, Without<DrawToward>>,
 ) {
     for (mut draw_toward, mut transform) in &mut entities {
         let last_speed = draw_toward.speed;
         draw_toward.speed += draw_toward.acceleration * time.delta_seconds();
         let average_speed = (last_speed + draw_toward.speed) / 2.0;
-        if let Ok(global_transform) = global_transforms.get(draw_toward.target) {
-            if let Ok((direction, length)) = Dir2::new_and_length(
-                (global_transform.translation() - transform.translation).truncate(),
-            ) {
+        if let Ok(target) = targets.get(draw_toward.target) {
+            if let Ok((direction, length)) =
+                Dir2::new_and_length((target.translation - transform.translation).truncate())
+            {
                 let distance = (average_speed * time.delta_seconds()).min(length);
 
                 transform.translation += (direction * distance).extend(0.0);
@@ -106,30 +127,40 @@ fn draw_toward(
     }
 }
 
-#[derive(Resource, Default, Reflect)]
+#[derive(Resource, Default, Reflect, Clone, Serialize, Deserialize)]
 #[reflect(Resource)]
 pub struct Wallet {
     pub amount: u32,
 }
 
+/// Triggered whenever coins are added to the [`Wallet`].
+#[derive(Event)]
+pub struct CoinsEarned {
+    pub amount: u32,
+}
+
 fn player_collects_items(
+    grid: Res<SpatialGrid<Item>>,
     mut wallet: ResMut<Wallet>,
-    players: Query<&GlobalTransform, With<Player>>,
-    items: Query<(Entity, &GlobalTransform), With<Item>>,
+    players: Query<&Transform, With<Player>>,
+    items: Query<&Transform, With<Item>>,
     mut commands: Commands,
 ) {
     let Ok(player) = players.get_single() else {
         return;
     };
 
-    for (item, transform) in &items {
-        if (player.translation() - transform.translation())
-            .truncate()
-            .length()
-            <= 50.0
-        {
+    let player_position = player.translation.truncate();
+    // Items drawn toward the player move quickly, so look further than the pickup radius
+    // and check where they are now.
+    for (item, _) in grid.query(player_position, ITEM_DRAW_RADIUS) {
+        let Ok(transform) = items.get(item) else {
+            continue;
+        };
+        if player_position.distance(transform.translation.truncate()) <= ITEM_PICKUP_RADIUS {
             commands.entity(item).despawn_recursive();
             wallet.amount += 1;
+            commands.trigger(CoinsEarned { amount: 1 });
         }
     }
 }
@@ -193,3 +224,139 @@ fn setup_wallet_display(mut commands: Commands) {
             ));
         });
 }
+
+#[derive(Resource, Reflect, Clone, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub struct SeedInventory {
+    pub counts: HashMap<PlantKey, u32>,
+    /// The seed type the planter uses.
+    pub active: PlantKey,
+}
+
+impl Default for SeedInventory {
+    fn default() -> Self {
+        Self {
+            counts: [(PlantKey::Weed, 10), (PlantKey::Flower, 3)].into(),
+            active: PlantKey::Weed,
+        }
+    }
+}
+
+impl SeedInventory {
+    pub fn count(&self, species: PlantKey) -> u32 {
+        self.counts.get(&species).copied().unwrap_or(0)
+    }
+
+    /// Use up one seed, returning `false` if there are none left.
+    pub fn take(&mut self, species: PlantKey) -> bool {
+        match self.counts.get_mut(&species) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn add(&mut self, species: PlantKey, amount: u32) {
+        *self.counts.entry(species).or_default() += amount;
+    }
+
+    /// Make the next seed type active.
+    pub fn cycle(&mut self) {
+        let index = PlantKey::ALL
+            .iter()
+            .position(|species| *species == self.active)
+            .unwrap_or(0);
+        self.active = PlantKey::ALL[(index + 1) % PlantKey::ALL.len()];
+    }
+}
+
+/// Trigger this event when the player tries to plant a seed they don't have.
+#[derive(Event)]
+pub struct OutOfSeeds {
+    pub species: PlantKey,
+}
+
+fn reset_seed_inventory(mut seed_inventory: ResMut<SeedInventory>) {
+    *seed_inventory = SeedInventory::default();
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct SeedDisplay {
+    species: PlantKey,
+    /// Highlights the display after a failed planting.
+    flash: Timer,
+}
+
+fn flash_seed_display(trigger: Trigger<OutOfSeeds>, mut displays: Query<&mut SeedDisplay>) {
+    for mut display in &mut displays {
+        if display.species == trigger.event().species {
+            display.flash.reset();
+        }
+    }
+}
+
+fn update_seed_display(
+    time: Res<Time>,
+    seed_inventory: Res<SeedInventory>,
+    species_handles: Res<HandleMap<PlantKey>>,
+    species_assets: Res<Assets<PlantSpecies>>,
+    mut displays: Query<(&mut SeedDisplay, &mut Text)>,
+) {
+    for (mut display, mut text) in &mut displays {
+        display.flash.tick(time.delta());
+
+        let name = species_assets
+            .get(&species_handles[&display.species])
+            .map_or("Seeds", |species| species.name.as_str());
+        let section = &mut text.sections[0];
+        section.value = format!("{name}: {}", seed_inventory.count(display.species));
+        section.style.color = if !display.flash.finished() {
+            RED.into()
+        } else if display.species == seed_inventory.active {
+            YELLOW.into()
+        } else {
+            WHITE.into()
+        };
+    }
+}
+
+fn setup_seed_display(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("Bottom Display"),
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    bottom: Val::Px(0.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(40.0),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                ..default()
+            },
+            StateScoped(Screen::Playing),
+        ))
+        .with_children(|children| {
+            for species in PlantKey::ALL {
+                let mut flash = Timer::new(Duration::from_millis(500), TimerMode::Once);
+                flash.tick(flash.duration());
+                children.spawn((
+                    TextBundle::from_section(
+                        "",
+                        TextStyle {
+                            font: default(),
+                            font_size: 50.0,
+                            color: WHITE.into(),
+                        },
+                    ),
+                    SeedDisplay { species, flash },
+                ));
+            }
+        });
+}
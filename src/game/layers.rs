@@ -1,4 +1,8 @@
+pub const DAMAGE_NUMBERS: f32 = 1.0;
+pub const HEALTH_BARS: f32 = 0.5;
 pub const PLAYER: f32 = 0.0;
 pub const BULLETS: f32 = -0.1;
 pub const ENEMIES: f32 = -0.5;
+pub const WALLS: f32 = -0.8;
 pub const SOIL: f32 = -1.0;
+pub const TERRAIN: f32 = -2.0;
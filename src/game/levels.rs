@@ -0,0 +1,222 @@
+//! Level layouts, loaded from `.level.ron` files.
+//! A layout is a grid of tiles, one character each, with the first row at the top:
+//!
+//! | Character | Tile |
+//! |-----------|------|
+//! | `S` | Soil to plant in |
+//! | `.` | A path |
+//! | `~` | Water, which can't be walked through but can be shot across |
+//! | `#` | A wall, which blocks movement, bullets and line of sight |
+//! | `@` | The player's start, on a soil tile |
+//!
+//! Add a new level by writing a file in `assets/levels/` and
+//! registering it in [`HandleMap<LevelKey>`](super::assets::HandleMap) and [`LevelKey::ALL`].
+
+use bevy::prelude::*;
+use serde::Deserialize;
+use thiserror::Error;
+
+use super::assets::{LevelKey, RonAsset, RonAssetLoader};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<LevelLayout>();
+    app.register_asset_loader(RonAssetLoader::<LevelLayout>::new(&["level.ron"]));
+
+    app.register_type::<SelectedLevel>();
+    app.init_resource::<SelectedLevel>();
+}
+
+/// The level the next run is played in.
+#[derive(Resource, Reflect, Default, Clone, Copy, Debug)]
+#[reflect(Resource)]
+pub struct SelectedLevel(pub LevelKey);
+
+#[derive(Asset, TypePath, Debug, Clone, Deserialize)]
+pub struct LevelLayout {
+    pub name: String,
+    /// The width and height of a tile.
+    pub tile_size: f32,
+    /// See [`Level::camera_scale`](super::spawn::level::Level::camera_scale).
+    pub camera_scale: f32,
+    /// See [`Level::wrap`](super::spawn::level::Level::wrap).
+    pub wrap: bool,
+    pub tiles: TileGrid,
+}
+
+impl RonAsset for LevelLayout {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect)]
+pub enum Tile {
+    Soil,
+    Path,
+    Water,
+    Wall,
+    Spawn,
+}
+
+impl Tile {
+    fn from_char(character: char) -> Option<Self> {
+        match character {
+            'S' => Some(Tile::Soil),
+            '.' => Some(Tile::Path),
+            '~' => Some(Tile::Water),
+            '#' => Some(Tile::Wall),
+            '@' => Some(Tile::Spawn),
+            _ => None,
+        }
+    }
+
+    /// Whether entities are kept out of the tile.
+    pub fn is_solid(self) -> bool {
+        matches!(self, Tile::Water | Tile::Wall)
+    }
+}
+
+/// A rectangle of tiles, with exactly one [`Tile::Spawn`].
+#[derive(Clone, Debug, Deserialize)]
+#[serde(try_from = "Vec<String>")]
+pub struct TileGrid {
+    width: usize,
+    height: usize,
+    tiles: Vec<Tile>,
+}
+
+#[non_exhaustive]
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum TileGridError {
+    #[error("The level has no tiles")]
+    Empty,
+    #[error("Row {row} is {width} tiles wide, but the first row is {expected}")]
+    RaggedRow {
+        row: usize,
+        width: usize,
+        expected: usize,
+    },
+    #[error("Unknown tile {tile:?} in row {row}, column {column}")]
+    UnknownTile {
+        tile: char,
+        row: usize,
+        column: usize,
+    },
+    #[error("The level needs exactly one spawn point, but has {0}")]
+    Spawns(usize),
+}
+
+impl TryFrom<Vec<String>> for TileGrid {
+    type Error = TileGridError;
+
+    fn try_from(rows: Vec<String>) -> Result<Self, Self::Error> {
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        if width == 0 {
+            return Err(TileGridError::Empty);
+        }
+        let mut tiles = Vec::with_capacity(width * rows.len());
+        for (row, line) in rows.iter().enumerate() {
+            let row_width = line.chars().count();
+            if row_width != width {
+                return Err(TileGridError::RaggedRow {
+                    row,
+                    width: row_width,
+                    expected: width,
+                });
+            }
+            for (column, character) in line.chars().enumerate() {
+                let tile = Tile::from_char(character).ok_or(TileGridError::UnknownTile {
+                    tile: character,
+                    row,
+                    column,
+                })?;
+                tiles.push(tile);
+            }
+        }
+        let spawns = tiles.iter().filter(|&&tile| tile == Tile::Spawn).count();
+        if spawns != 1 {
+            return Err(TileGridError::Spawns(spawns));
+        }
+        Ok(Self {
+            width,
+            height: rows.len(),
+            tiles,
+        })
+    }
+}
+
+impl TileGrid {
+    /// Every tile, along with the position of its center in tiles, relative to the center of the grid.
+    pub fn iter(&self) -> impl Iterator<Item = (Vec2, Tile)> + '_ {
+        let half_size = Vec2::new(self.width as f32 - 1.0, self.height as f32 - 1.0) / 2.0;
+        self.tiles.iter().enumerate().map(move |(index, &tile)| {
+            let column = (index % self.width) as f32;
+            let row = (index / self.width) as f32;
+            (Vec2::new(column - half_size.x, half_size.y - row), tile)
+        })
+    }
+
+    /// The size of the grid, in tiles.
+    pub fn size(&self) -> Vec2 {
+        Vec2::new(self.width as f32, self.height as f32)
+    }
+
+    /// Rectangles covering every tile that matches `include`, with neighbouring tiles merged
+    /// into as few rectangles as a greedy pass finds. Measured in tiles, like [`TileGrid::iter`].
+    pub fn merged(&self, include: impl Fn(Tile) -> bool) -> Vec<Rect> {
+        let mut covered = vec![false; self.tiles.len()];
+        let free = |covered: &[bool], column: usize, row: usize| {
+            let index = row * self.width + column;
+            !covered[index] && include(self.tiles[index])
+        };
+        let half_size = self.size() / 2.0;
+        let mut rects = Vec::new();
+        for row in 0..self.height {
+            for column in 0..self.width {
+                if !free(&covered, column, row) {
+                    continue;
+                }
+                // Grow right as far as possible, then down while the whole span fits.
+                let mut end_column = column + 1;
+                while end_column < self.width && free(&covered, end_column, row) {
+                    end_column += 1;
+                }
+                let mut end_row = row + 1;
+                while end_row < self.height
+                    && (column..end_column).all(|c| free(&covered, c, end_row))
+                {
+                    end_row += 1;
+                }
+                for r in row..end_row {
+                    covered[r * self.width + column..r * self.width + end_column].fill(true);
+                }
+                rects.push(Rect::new(
+                    column as f32 - half_size.x,
+                    half_size.y - end_row as f32,
+                    end_column as f32 - half_size.x,
+                    half_size.y - row as f32,
+                ));
+            }
+        }
+        rects
+    }
+}
+
+impl LevelLayout {
+    /// The area covered by tiles, centered on the origin.
+    pub fn bounds(&self) -> Rect {
+        Rect::from_center_size(Vec2::ZERO, self.tiles.size() * self.tile_size)
+    }
+
+    /// Every tile, along with the position of its center.
+    pub fn tiles(&self) -> impl Iterator<Item = (Vec2, Tile)> + '_ {
+        self.tiles
+            .iter()
+            .map(|(position, tile)| (position * self.tile_size, tile))
+    }
+
+    /// The areas covered by solid tiles, with neighbouring tiles merged together.
+    pub fn solid_areas(&self) -> Vec<Rect> {
+        self.tiles
+            .merged(Tile::is_solid)
+            .into_iter()
+            .map(|area| Rect::from_corners(area.min * self.tile_size, area.max * self.tile_size))
+            .collect()
+    }
+}
@@ -5,19 +5,43 @@ use bevy::prelude::*;
 mod animation;
 pub mod assets;
 pub mod audio;
-mod bullets;
+pub mod bullets;
+pub mod camera;
 pub mod collision_groups;
-mod enemies;
+pub mod cycle;
+mod damage_numbers;
+pub mod enemies;
 mod ghost;
 pub mod health;
-mod items;
+mod health_bar;
+pub mod input;
+pub mod interpolation;
+pub mod items;
 pub mod layers;
-mod movement;
-mod plant;
+pub mod levels;
+pub mod movement;
+pub mod plant;
+pub mod replay;
+pub mod rng;
+pub mod rounds;
+pub mod save;
+pub mod shop;
+pub mod spatial;
 pub mod spawn;
+pub mod species;
+pub mod stats;
+pub mod status;
 mod tools;
+pub mod weapons;
 
 pub(super) fn plugin(app: &mut App) {
+    // Register asset types before `assets::plugin` starts loading them.
+    app.add_plugins((
+        species::plugin,
+        shop::plugin,
+        weapons::plugin,
+        levels::plugin,
+    ));
     app.add_plugins((
         animation::plugin,
         audio::plugin,
@@ -25,11 +49,25 @@ pub(super) fn plugin(app: &mut App) {
         ghost::plugin,
         movement::plugin,
         spawn::plugin,
+        input::plugin,
+        interpolation::plugin,
+        rng::plugin,
+        replay::plugin,
+    ));
+    app.add_plugins((
         plant::plugin,
         enemies::plugin,
         bullets::plugin,
+        camera::plugin,
         health::plugin,
+        health_bar::plugin,
         items::plugin,
         tools::plugin,
+        cycle::plugin,
+        damage_numbers::plugin,
+        rounds::plugin,
+        stats::plugin,
+        status::plugin,
+        save::plugin,
     ));
 }
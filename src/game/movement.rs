@@ -1,7 +1,7 @@
 //! Handle player input and translate it into movement.
-//! Note that the approach used here is simple for demonstration purposes.
-//! If you want to move the player in a smoother way,
-//! consider using a [fixed timestep](https://github.com/bevyengine/bevy/blob/latest/examples/movement/physics_in_fixed_timestep.rs).
+//! Movement input is read and applied in `FixedUpdate` so that it doesn't depend on the frame rate,
+//! while dash presses are recorded every frame so that none are missed.
+//! See [`interpolation`](super::interpolation) for how it's drawn smoothly anyway.
 
 use std::time::Duration;
 
@@ -9,27 +9,42 @@ use bevy::{prelude::*, window::PrimaryWindow};
 
 use crate::AppSet;
 
-use super::ghost::{GhostSet, GhostSpawner};
+use super::{
+    ghost::GhostSpawner,
+    input::{Action, ActionState},
+    interpolation::InterpolateTranslation,
+    spawn::level::Level,
+    status::StatusEffects,
+};
 
-pub(super) fn plugin(app: &mut App) {
+pub fn plugin(app: &mut App) {
     // Record directional input as movement controls.
-    app.register_type::<(MovementController, DashIntent)>();
+    app.register_type::<(MovementController, Facing, DashIntent)>();
     app.init_resource::<DashIntent>();
+    // The controller is read again every fixed step, so that it's never a step behind.
+    // A dash press only shows up for one frame, which may not run a fixed step,
+    // so its intent is recorded every frame and kept for `DashSettings::intent_window`.
     app.add_systems(
-        Update,
-        (record_movement_controller, record_dash_intent).in_set(AppSet::RecordInput),
+        FixedUpdate,
+        record_movement_controller.in_set(AppSet::RecordInput),
     );
+    app.add_systems(Update, record_dash_intent.in_set(AppSet::RecordInput));
 
     // Apply movement based on controls.
-    app.register_type::<(MovementSettings, WrapWithinWindow, DashSettings, Dash)>();
+    // `FixedUpdate` runs before `Update`, so ghosts are always spawned
+    // after the dash that adds their `GhostSpawner` has started.
+    app.register_type::<(MovementSettings, StayInLevel, DashSettings, Dash, Knockback)>();
     app.add_systems(
-        Update,
+        FixedUpdate,
         (
-            start_dash.before(GhostSet::Update),
+            start_dash,
             apply_movement,
-            apply_dash.after(GhostSet::Update),
+            apply_dash,
             stop_dash,
-            wrap_within_window,
+            apply_knockback,
+            wrap_within_window.run_if(level_wraps),
+            confine_to_level.run_if(not(level_wraps)),
+            keep_out_of_solid_tiles.run_if(resource_exists::<Level>),
         )
             .chain()
             .in_set(AppSet::Update),
@@ -46,32 +61,30 @@ impl MovementController {
     }
 }
 
-fn record_movement_controller(
-    input: Res<ButtonInput<KeyCode>>,
-    mut controller_query: Query<&mut MovementController>,
-) {
-    // Collect directional input.
-    let mut intent = Vec2::ZERO;
-    if input.pressed(KeyCode::KeyW) || input.pressed(KeyCode::ArrowUp) {
-        intent.y += 1.0;
-    }
-    if input.pressed(KeyCode::KeyS) || input.pressed(KeyCode::ArrowDown) {
-        intent.y -= 1.0;
-    }
-    if input.pressed(KeyCode::KeyA) || input.pressed(KeyCode::ArrowLeft) {
-        intent.x -= 1.0;
-    }
-    if input.pressed(KeyCode::KeyD) || input.pressed(KeyCode::ArrowRight) {
-        intent.x += 1.0;
+/// The direction an entity last moved in, kept while it stands still.
+#[derive(Component, Reflect, Clone, Copy)]
+#[reflect(Component)]
+pub struct Facing(pub Dir2);
+
+impl Default for Facing {
+    fn default() -> Self {
+        Self(Dir2::EAST)
     }
+}
 
-    // Normalize so that diagonal movement has the same speed as
-    // horizontal and vertical movement.
-    let intent = intent.normalize_or_zero();
+fn record_movement_controller(
+    actions: Res<ActionState>,
+    mut controller_query: Query<(&mut MovementController, Option<&mut Facing>)>,
+) {
+    // Keyboard directions are already normalized, and analog sticks keep their magnitude.
+    let intent = actions.axis(Action::Move);
 
     // Apply movement intent to controllers.
-    for mut controller in &mut controller_query {
+    for (mut controller, facing) in &mut controller_query {
         controller.0 = intent;
+        if let (Some(mut facing), Some(direction)) = (facing, controller.direction()) {
+            facing.0 = direction;
+        }
     }
 }
 
@@ -106,12 +119,19 @@ pub struct Dash {
 fn apply_movement(
     time: Res<Time>,
     mut movement_query: Query<
-        (&MovementController, &MovementSettings, &mut Transform),
+        (
+            &MovementController,
+            &MovementSettings,
+            Option<&StatusEffects>,
+            &mut Transform,
+        ),
         Without<Dash>,
     >,
 ) {
-    for (controller, movement, mut transform) in &mut movement_query {
-        let velocity = movement.max_speed * controller.0;
+    for (controller, movement, status_effects, mut transform) in &mut movement_query {
+        let speed =
+            movement.max_speed * status_effects.map_or(1.0, StatusEffects::speed_multiplier);
+        let velocity = speed * controller.0;
         transform.translation += velocity.extend(0.0) * time.delta_seconds();
     }
 }
@@ -123,18 +143,27 @@ pub struct DashIntent {
 }
 
 fn record_dash_intent(
-    input: Res<ButtonInput<KeyCode>>,
-    time: Res<Time>,
+    actions: Res<ActionState>,
+    // Dashes start in a fixed step, so use the same clock.
+    time: Res<Time<Fixed>>,
     mut intent: ResMut<DashIntent>,
 ) {
-    if input.just_pressed(KeyCode::Space) {
+    if actions.just_pressed(Action::Dash) {
         intent.at_time = Some(time.elapsed());
     }
 }
 
 fn start_dash(
     time: Res<Time>,
-    dash_settings_query: Query<(Entity, &DashSettings, &MovementController), Without<Dash>>,
+    dash_settings_query: Query<
+        (
+            Entity,
+            &DashSettings,
+            &MovementController,
+            Option<&StatusEffects>,
+        ),
+        Without<Dash>,
+    >,
     dash_intent: Res<DashIntent>,
     mut commands: Commands,
 ) {
@@ -143,7 +172,10 @@ fn start_dash(
     };
     let dash_window_end = time.elapsed();
     let dash_window_start = time.elapsed() - time.delta();
-    for (entity, settings, movement_controller) in &dash_settings_query {
+    for (entity, settings, movement_controller, status_effects) in &dash_settings_query {
+        if status_effects.is_some_and(StatusEffects::is_stunned) {
+            continue;
+        }
         if let Some(direction) = movement_controller.direction() {
             if dash_window_end - settings.intent_window <= at_time {
                 commands.entity(entity).insert((
@@ -191,22 +223,82 @@ fn stop_dash(
     }
 }
 
+/// A push, like from being hit, that slows down until it's removed.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Knockback {
+    pub velocity: Vec2,
+}
+
+/// How quickly knockback slows down, as a fraction of its velocity per second.
+const KNOCKBACK_DECAY: f32 = 8.0;
+
+fn apply_knockback(
+    time: Res<Time>,
+    mut knockback_query: Query<(Entity, &mut Knockback, &mut Transform)>,
+    mut commands: Commands,
+) {
+    let dt = time.delta_seconds();
+    for (entity, mut knockback, mut transform) in &mut knockback_query {
+        transform.translation += (knockback.velocity * dt).extend(0.0);
+        knockback.velocity *= (-KNOCKBACK_DECAY * dt).exp();
+        if knockback.velocity.length() < 1.0 {
+            commands.entity(entity).remove::<Knockback>();
+        }
+    }
+}
+
+/// Keeps an entity in the level, by wrapping it around the screen if [`Level::wrap`] is set
+/// and by stopping it at [`Level::bounds`] otherwise. It can't enter [`Level::solid`] tiles either way.
 #[derive(Component, Reflect)]
 #[reflect(Component)]
-pub struct WrapWithinWindow;
+pub struct StayInLevel;
+
+fn level_wraps(level: Option<Res<Level>>) -> bool {
+    level.is_none_or(|level| level.wrap)
+}
 
 fn wrap_within_window(
     window_query: Query<&Window, With<PrimaryWindow>>,
     cameras: Query<&OrthographicProjection, With<Camera>>,
-    mut wrap_query: Query<&mut Transform, With<WrapWithinWindow>>,
+    mut wrap_query: Query<(&mut Transform, Option<&mut InterpolateTranslation>), With<StayInLevel>>,
 ) {
-    let mut size = window_query.single().size();
-    size *= cameras.single().scale;
+    let (Ok(window), Ok(projection)) = (window_query.get_single(), cameras.get_single()) else {
+        return;
+    };
+    let mut size = window.size();
+    size *= projection.scale;
     size += 256.0;
     let half_size = size / 2.0;
-    for mut transform in &mut wrap_query {
+    for (mut transform, interpolation) in &mut wrap_query {
         let position = transform.translation.xy();
+        // Leave everything inside alone, so that it keeps being interpolated.
+        if position.abs().cmple(half_size).all() {
+            continue;
+        }
         let wrapped = (position + half_size).rem_euclid(size) - half_size;
         transform.translation = wrapped.extend(transform.translation.z);
+        if let Some(mut interpolation) = interpolation {
+            interpolation.teleport();
+        }
+    }
+}
+
+fn confine_to_level(level: Res<Level>, mut query: Query<&mut Transform, With<StayInLevel>>) {
+    for mut transform in &mut query {
+        let position = transform.translation.xy();
+        let confined = position.clamp(level.bounds.min, level.bounds.max);
+        if confined != position {
+            transform.translation = confined.extend(transform.translation.z);
+        }
+    }
+}
+
+/// Push entities out of solid tiles the shortest way.
+fn keep_out_of_solid_tiles(level: Res<Level>, mut query: Query<&mut Transform, With<StayInLevel>>) {
+    for mut transform in &mut query {
+        if let Some(position) = level.push_out_of_solid(transform.translation.xy()) {
+            transform.translation = position.extend(transform.translation.z);
+        }
     }
 }
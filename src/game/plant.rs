@@ -1,47 +1,142 @@
-use std::time::Duration;
+use std::{f32::consts::TAU, time::Duration};
 
 use bevy::{
-    color::palettes::css::{GREEN, GREEN_YELLOW, SADDLE_BROWN, SANDY_BROWN},
+    color::palettes::css::{SADDLE_BROWN, SANDY_BROWN, WHITE},
     prelude::*,
 };
-use bevy_rapier2d::pipeline::CollisionEvent;
+use bevy_rapier2d::{pipeline::CollisionEvent, plugin::RapierContext};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 
-use super::spawn::enemy::SpawnEnemy;
+use super::{
+    assets::{HandleMap, PlantKey},
+    camera::{AddTrauma, HATCH_TRAUMA},
+    cycle::{DayCycle, DayPhase, PhaseChanged},
+    input::{Action, ActionState},
+    items::DROP_SCATTER,
+    items::{CoinsEarned, OutOfSeeds, SeedInventory, SpawnItem, Wallet},
+    rng::{point_near, GameRng},
+    rounds::{DifficultyCurve, Round},
+    spawn::enemy::SpawnEnemy,
+    species::{GrowthStage, MaturesInto, PlantSpecies},
+};
 
 pub fn plugin(app: &mut App) {
+    app.register_type::<(Soil, Plant, MoistureSettings)>();
+    app.init_resource::<MoistureSettings>();
     app.add_systems(
         Update,
-        (trigger_seed_events, trigger_water_event, growth, soil_color).chain(),
+        (
+            trigger_seed_events,
+            trigger_water_event,
+            trigger_harvest_event,
+            use_tool,
+            evaporate,
+            growth,
+            ripen,
+            wilt,
+            soil_color,
+        )
+            .chain(),
     );
     app.observe(plant_seed)
         .observe(water_soil)
-        .observe(finish_growing);
+        .observe(harvest_plant)
+        .observe(finish_growing)
+        .observe(restore_plant)
+        .observe(match_new_plant_to_phase)
+        .observe(match_plants_to_phase);
 }
 
 #[derive(Component, Reflect, Default)]
 #[reflect(Component, Default)]
 pub struct Soil {
     pub plant: Option<Entity>,
-    pub state: SoilState,
+    /// How wet the soil is, from `0.0` (dry) to `1.0` (soaked).
+    pub moisture: f32,
 }
 
-#[derive(Default, Reflect, PartialEq, Eq)]
-pub enum SoilState {
-    #[default]
-    Dry,
-    Wet,
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct MoistureSettings {
+    /// Moisture added each time the water tool touches the soil.
+    pub water_per_contact: f32,
+    /// Moisture lost per second.
+    pub evaporation_rate: f32,
+    /// How long a plant survives in completely dry soil.
+    pub wilt_after: Duration,
+}
+
+impl Default for MoistureSettings {
+    fn default() -> Self {
+        Self {
+            water_per_contact: 0.5,
+            evaporation_rate: 0.1,
+            wilt_after: Duration::from_secs(15),
+        }
+    }
 }
 
-#[derive(Component, Reflect)]
+#[derive(Component, Reflect, Clone, Serialize, Deserialize)]
 #[reflect(Component)]
-struct Plant {
+pub struct Plant {
+    species: PlantKey,
     growth_timer: Timer,
-    stages: u8,
-    current_stage: u8,
+    current_stage: usize,
+    /// How long the soil has been completely dry.
+    dry_time: Duration,
+    state: PlantState,
+}
+
+#[derive(Reflect, Default, Clone, Serialize, Deserialize)]
+enum PlantState {
+    #[default]
+    Growing,
+    /// The plant has grown through every stage and can be harvested
+    /// until the timer runs out and it matures.
+    Ripening { hatch_timer: Timer },
+}
+
+impl Plant {
+    /// Pause the timers that shouldn't run during `phase`.
+    /// Plants only grow in daylight, and ripe plants wait for nightfall before they hatch.
+    fn match_phase(&mut self, phase: DayPhase) {
+        let is_day = phase == DayPhase::Day;
+        set_paused(&mut self.growth_timer, !is_day);
+        if let PlantState::Ripening { hatch_timer } = &mut self.state {
+            set_paused(hatch_timer, is_day);
+        }
+    }
+}
+
+fn set_paused(timer: &mut Timer, paused: bool) {
+    if paused {
+        timer.pause();
+    } else {
+        timer.unpause();
+    }
+}
+
+fn match_new_plant_to_phase(
+    trigger: Trigger<OnAdd, Plant>,
+    day_cycle: Res<DayCycle>,
+    mut plants: Query<&mut Plant>,
+) {
+    if let Ok(mut plant) = plants.get_mut(trigger.entity()) {
+        plant.match_phase(day_cycle.phase);
+    }
+}
+
+fn match_plants_to_phase(trigger: Trigger<PhaseChanged>, mut plants: Query<&mut Plant>) {
+    for mut plant in &mut plants {
+        plant.match_phase(trigger.event().phase);
+    }
 }
 
 #[derive(Event)]
-struct PlantSeed;
+struct PlantSeed {
+    species: PlantKey,
+}
 
 #[derive(Component)]
 pub struct Planter;
@@ -50,6 +145,7 @@ fn trigger_seed_events(
     mut events: EventReader<CollisionEvent>,
     planters: Query<&Planter>,
     soil: Query<&Soil>,
+    seed_inventory: Res<SeedInventory>,
     mut commands: Commands,
 ) {
     for event in events.read() {
@@ -66,12 +162,25 @@ fn trigger_seed_events(
                 continue;
             };
 
-            commands.trigger_targets(PlantSeed, entity);
+            commands.trigger_targets(
+                PlantSeed {
+                    species: seed_inventory.active,
+                },
+                entity,
+            );
         }
     }
 }
 
-fn plant_seed(trigger: Trigger<PlantSeed>, mut soil: Query<&mut Soil>, mut commands: Commands) {
+fn plant_seed(
+    trigger: Trigger<PlantSeed>,
+    mut soil: Query<&mut Soil>,
+    mut seed_inventory: ResMut<SeedInventory>,
+    species_handles: Res<HandleMap<PlantKey>>,
+    species_assets: Res<Assets<PlantSpecies>>,
+    mut rng: ResMut<GameRng>,
+    mut commands: Commands,
+) {
     let Ok(mut soil) = soil.get_mut(trigger.entity()) else {
         return;
     };
@@ -80,25 +189,101 @@ fn plant_seed(trigger: Trigger<PlantSeed>, mut soil: Query<&mut Soil>, mut comma
         return;
     }
 
-    let stages = 2;
+    let species_key = trigger.event().species;
+    let Some(species) = species_assets.get(&species_handles[&species_key]) else {
+        return;
+    };
+    let Some(first_stage) = species.stages.first() else {
+        return;
+    };
+
+    if !seed_inventory.take(species_key) {
+        commands.trigger(OutOfSeeds {
+            species: species_key,
+        });
+        return;
+    }
+
+    let mut sprite = Sprite {
+        anchor: bevy::sprite::Anchor::BottomCenter,
+        ..default()
+    };
+    let mut texture = Handle::default();
+    first_stage.look.apply(&mut sprite, &mut texture);
 
     let plant = commands
         .spawn((
+            Name::new(species.name.clone()),
             SpriteBundle {
-                transform: Transform::from_scale(Vec3::new(0.5, 1.0 / stages as f32, 1.0))
-                    .with_translation(Vec3::new(0.0, 0.0, 0.01)),
-                sprite: Sprite {
-                    color: GREEN_YELLOW.into(),
-                    anchor: bevy::sprite::Anchor::BottomCenter,
-                    ..default()
-                },
+                transform: Transform::from_translation(Vec3::new(0.0, 0.0, 0.01)),
+                sprite,
+                texture,
                 ..default()
             },
             Plant {
-                growth_timer: Timer::new(Duration::from_secs(1), TimerMode::Once),
-                stages,
+                species: species_key,
+                growth_timer: stage_timer(first_stage, &mut rng),
                 current_stage: 0,
+                dry_time: Duration::ZERO,
+                state: PlantState::Growing,
+            },
+        ))
+        .set_parent(trigger.entity())
+        .id();
+
+    soil.plant = Some(plant);
+}
+
+/// Put a plant back into the soil, picking up where it left off.
+/// Used when continuing a saved run.
+#[derive(Event)]
+pub struct RestorePlant {
+    pub plant: Plant,
+}
+
+fn restore_plant(
+    trigger: Trigger<RestorePlant>,
+    mut soil: Query<&mut Soil>,
+    species_handles: Res<HandleMap<PlantKey>>,
+    species_assets: Res<Assets<PlantSpecies>>,
+    mut commands: Commands,
+) {
+    let Ok(mut soil) = soil.get_mut(trigger.entity()) else {
+        return;
+    };
+    let plant = trigger.event().plant.clone();
+    let Some(species) = species_assets.get(&species_handles[&plant.species]) else {
+        return;
+    };
+    // Ripening plants have grown past their last stage.
+    let Some(stage) = species
+        .stages
+        .get(plant.current_stage)
+        .or(species.stages.last())
+    else {
+        return;
+    };
+
+    let mut sprite = Sprite {
+        anchor: bevy::sprite::Anchor::BottomCenter,
+        ..default()
+    };
+    let mut texture = Handle::default();
+    stage.look.apply(&mut sprite, &mut texture);
+
+    if let Some(old_plant) = soil.plant {
+        commands.entity(old_plant).despawn_recursive();
+    }
+    let plant = commands
+        .spawn((
+            Name::new(species.name.clone()),
+            SpriteBundle {
+                transform: Transform::from_translation(Vec3::new(0.0, 0.0, 0.01)),
+                sprite,
+                texture,
+                ..default()
             },
+            plant,
         ))
         .set_parent(trigger.entity())
         .id();
@@ -137,74 +322,299 @@ fn trigger_water_event(
     }
 }
 
-fn water_soil(trigger: Trigger<WaterSoil>, mut soil: Query<&mut Soil>) {
+fn water_soil(
+    trigger: Trigger<WaterSoil>,
+    settings: Res<MoistureSettings>,
+    mut soil: Query<&mut Soil>,
+) {
     let Ok(mut soil) = soil.get_mut(trigger.entity()) else {
         return;
     };
 
-    soil.state = SoilState::Wet;
+    soil.moisture = (soil.moisture + settings.water_per_contact).min(1.0);
+}
+
+fn evaporate(time: Res<Time>, settings: Res<MoistureSettings>, mut soil: Query<&mut Soil>) {
+    for mut soil in &mut soil {
+        soil.moisture = (soil.moisture - settings.evaporation_rate * time.delta_seconds()).max(0.0);
+    }
 }
 
 #[derive(Event)]
 struct FinishedGrowing;
 
+/// How much a stage's duration varies from plant to plant, as a fraction of it.
+const GROWTH_VARIATION: f32 = 0.1;
+
+/// A timer for growing through `stage`, a little faster or slower than the species asks for
+/// so that plants sown together don't all ripen at once.
+fn stage_timer(stage: &GrowthStage, rng: &mut GameRng) -> Timer {
+    let variation = rng
+        .gameplay()
+        .gen_range(-GROWTH_VARIATION..=GROWTH_VARIATION);
+    Timer::from_seconds(stage.duration_secs * (1.0 + variation), TimerMode::Once)
+}
+
+/// Only ticks in daylight, since [`Plant::match_phase`] pauses the timer at night.
 fn growth(
     time: Res<Time>,
-    mut plants: Query<(Entity, &mut Plant, &mut Sprite, &Parent, &mut Transform)>,
-    mut soil: Query<&mut Soil>,
-    mut commands: Commands,
+    mut plants: Query<(&mut Plant, &mut Sprite, &mut Handle<Image>, &Parent)>,
+    soil: Query<&Soil>,
+    species_handles: Res<HandleMap<PlantKey>>,
+    species_assets: Res<Assets<PlantSpecies>>,
+    mut rng: ResMut<GameRng>,
 ) {
-    for (entity, mut plant, mut sprite, parent, mut transform) in &mut plants {
-        let Ok(mut soil) = soil.get_mut(parent.get()) else {
+    for (mut plant, mut sprite, mut texture, parent) in &mut plants {
+        let Ok(soil) = soil.get(parent.get()) else {
+            continue;
+        };
+        let Some(species) = species_assets.get(&species_handles[&plant.species]) else {
+            continue;
+        };
+        if !matches!(plant.state, PlantState::Growing) {
+            continue;
+        }
+        let Some(stage) = species.stages.get(plant.current_stage) else {
             continue;
         };
 
-        if soil.state != SoilState::Wet {
+        if soil.moisture <= 0.0 || soil.moisture < stage.water_needed {
             continue;
         }
 
-        plant.growth_timer.tick(time.delta());
-        sprite.color = GREEN_YELLOW
-            .mix(
-                &GREEN,
-                (plant.current_stage as f32 + plant.growth_timer.fraction()) / plant.stages as f32,
-            )
-            .into();
+        // Plants grow faster the wetter their soil is.
+        plant.growth_timer.tick(time.delta().mul_f32(soil.moisture));
         if plant.growth_timer.finished() {
-            soil.state = SoilState::Dry;
             plant.current_stage += 1;
-            transform.scale.y = (plant.current_stage + 1) as f32 / plant.stages as f32;
-            plant.growth_timer.reset();
-            if plant.current_stage >= plant.stages {
-                commands.trigger_targets(FinishedGrowing, entity);
+            if let Some(next_stage) = species.stages.get(plant.current_stage) {
+                next_stage.look.apply(&mut sprite, &mut texture);
+                plant.growth_timer = stage_timer(next_stage, &mut rng);
+            } else {
+                let mut hatch_timer = Timer::from_seconds(species.ripening_secs, TimerMode::Once);
+                // Growing only finishes in daylight, so wait for nightfall.
+                hatch_timer.pause();
+                plant.state = PlantState::Ripening { hatch_timer };
+            }
+        }
+    }
+}
+
+fn ripen(
+    time: Res<Time>,
+    mut plants: Query<(Entity, &mut Plant, &mut Sprite)>,
+    species_handles: Res<HandleMap<PlantKey>>,
+    species_assets: Res<Assets<PlantSpecies>>,
+    mut commands: Commands,
+) {
+    for (entity, mut plant, mut sprite) in &mut plants {
+        let Some(species) = species_assets.get(&species_handles[&plant.species]) else {
+            continue;
+        };
+        let PlantState::Ripening { hatch_timer } = &mut plant.state else {
+            continue;
+        };
+
+        // Paused until nightfall.
+        hatch_timer.tick(time.delta());
+        if hatch_timer.finished() {
+            commands.trigger_targets(FinishedGrowing, entity);
+            continue;
+        }
+
+        // Pulse towards white so ripe plants stand out.
+        if let Some(last_stage) = species.stages.last() {
+            let pulse = (time.elapsed_seconds() * TAU * 2.0).sin() * 0.5 + 0.5;
+            sprite.color = last_stage.look.color.mix(&WHITE.into(), pulse * 0.6);
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Harvester;
+
+#[derive(Event)]
+struct HarvestPlant;
+
+/// Triggered after the player harvested a ripe plant.
+#[derive(Event)]
+pub struct Harvested;
+
+fn trigger_harvest_event(
+    mut events: EventReader<CollisionEvent>,
+    harvesters: Query<&Harvester>,
+    soil: Query<&Soil>,
+    mut commands: Commands,
+) {
+    for event in events.read() {
+        if let CollisionEvent::Started(a, b, _) = *event {
+            let (_sensor, entity) = if harvesters.contains(a) {
+                (a, b)
+            } else if harvesters.contains(b) {
+                (b, a)
+            } else {
+                continue;
+            };
+
+            if !soil.contains(entity) {
+                continue;
+            };
+
+            commands.trigger_targets(HarvestPlant, entity);
+        }
+    }
+}
+
+/// Apply the held tool again to the soil it's touching,
+/// without having to step off the tile and back on.
+fn use_tool(
+    actions: Res<ActionState>,
+    rapier_context: Res<RapierContext>,
+    tools: Query<(Entity, Has<Planter>, Has<Water>, Has<Harvester>)>,
+    soil: Query<(), With<Soil>>,
+    seed_inventory: Res<SeedInventory>,
+    mut commands: Commands,
+) {
+    if !actions.just_pressed(Action::Use) {
+        return;
+    }
+
+    for (tool, planter, water, harvester) in &tools {
+        if !(planter || water || harvester) {
+            continue;
+        }
+        for (a, b, intersecting) in rapier_context.intersection_pairs_with(tool) {
+            let entity = if a == tool { b } else { a };
+            if !intersecting || !soil.contains(entity) {
+                continue;
+            }
+
+            if planter {
+                commands.trigger_targets(
+                    PlantSeed {
+                        species: seed_inventory.active,
+                    },
+                    entity,
+                );
+            }
+            if water {
+                commands.trigger_targets(WaterSoil, entity);
+            }
+            if harvester {
+                commands.trigger_targets(HarvestPlant, entity);
             }
         }
     }
 }
 
+fn harvest_plant(
+    trigger: Trigger<HarvestPlant>,
+    mut soil: Query<&mut Soil>,
+    plants: Query<&Plant>,
+    mut wallet: ResMut<Wallet>,
+    mut seed_inventory: ResMut<SeedInventory>,
+    species_handles: Res<HandleMap<PlantKey>>,
+    species_assets: Res<Assets<PlantSpecies>>,
+    mut commands: Commands,
+) {
+    let Ok(mut soil) = soil.get_mut(trigger.entity()) else {
+        return;
+    };
+    let Some(plant_entity) = soil.plant else {
+        return;
+    };
+    let Ok(plant) = plants.get(plant_entity) else {
+        return;
+    };
+    if !matches!(plant.state, PlantState::Ripening { .. }) {
+        return;
+    }
+    let Some(species) = species_assets.get(&species_handles[&plant.species]) else {
+        return;
+    };
+
+    wallet.amount += species.harvest.coins;
+    seed_inventory.add(plant.species, species.harvest.seeds);
+    soil.plant = None;
+    commands.entity(plant_entity).despawn_recursive();
+    commands.trigger(CoinsEarned {
+        amount: species.harvest.coins,
+    });
+    commands.trigger(Harvested);
+}
+
+/// How far from their plant hatched enemies can appear.
+const HATCH_SCATTER: f32 = 150.0;
+
 fn finish_growing(
     trigger: Trigger<FinishedGrowing>,
-    parents: Query<&Parent>,
+    plants: Query<(&Plant, &Parent)>,
     mut soil: Query<(&mut Soil, &GlobalTransform)>,
+    species_handles: Res<HandleMap<PlantKey>>,
+    species_assets: Res<Assets<PlantSpecies>>,
+    round: Res<Round>,
+    curve: Res<DifficultyCurve>,
+    mut rng: ResMut<GameRng>,
     mut commands: Commands,
 ) {
-    if let Ok(parent) = parents.get(trigger.entity()) {
+    if let Ok((plant, parent)) = plants.get(trigger.entity()) {
         if let Ok((mut soil, global_transform)) = soil.get_mut(parent.get()) {
             soil.plant = None;
-            commands.trigger(SpawnEnemy {
-                position: global_transform.translation().truncate(),
-            });
+            let position = global_transform.translation().truncate();
+            match species_assets
+                .get(&species_handles[&plant.species])
+                .map(|species| &species.matures_into)
+            {
+                Some(MaturesInto::Enemy { archetype }) => {
+                    commands.trigger(AddTrauma(HATCH_TRAUMA));
+                    for _ in 0..curve.spawn_count(round.number) {
+                        commands.trigger(SpawnEnemy {
+                            position: point_near(rng.gameplay(), position, HATCH_SCATTER),
+                            archetype: *archetype,
+                            health: None,
+                        });
+                    }
+                }
+                Some(MaturesInto::Item { amount }) => {
+                    for _ in 0..*amount {
+                        commands.trigger(SpawnItem {
+                            position: point_near(rng.gameplay(), position, DROP_SCATTER),
+                        });
+                    }
+                }
+                Some(MaturesInto::Nothing) | None => {}
+            }
         }
     }
     commands.entity(trigger.entity()).despawn_recursive();
 }
 
+fn wilt(
+    time: Res<Time>,
+    settings: Res<MoistureSettings>,
+    mut plants: Query<(Entity, &mut Plant, &Parent)>,
+    mut soil: Query<&mut Soil>,
+    mut commands: Commands,
+) {
+    for (entity, mut plant, parent) in &mut plants {
+        let Ok(mut soil) = soil.get_mut(parent.get()) else {
+            continue;
+        };
+
+        if soil.moisture > 0.0 {
+            plant.dry_time = Duration::ZERO;
+            continue;
+        }
+
+        plant.dry_time += time.delta();
+        if plant.dry_time >= settings.wilt_after {
+            soil.plant = None;
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
 fn soil_color(mut soil: Query<(&Soil, &mut Sprite)>) {
     for (soil, mut sprite) in &mut soil {
-        sprite.color = match soil.state {
-            SoilState::Dry => SANDY_BROWN,
-            SoilState::Wet => SADDLE_BROWN,
-        }
-        .into();
+        sprite.color = SANDY_BROWN.mix(&SADDLE_BROWN, soil.moisture).into();
     }
 }
@@ -0,0 +1,251 @@
+//! Record the player's actions frame by frame and play them back later,
+//! so a bug report can come with a replay that reproduces it exactly.
+//!
+//! A recording holds the run's seed and level and, for every frame, how long the frame took
+//! and the [`ActionState`]. Movement, dashes and tool switches all follow from those,
+//! so playing them back with the same frame times replays the same run.
+//! Menu buttons clicked with the mouse and settings like the aim mode aren't recorded,
+//! and recordings always start from a fresh run.
+//!
+//! Launch with `--record <path>` to record each run to a file, replacing the previous one,
+//! or with `--replay <path>` to play a recorded run back once a run in the same level starts.
+//! Headless tests can trigger [`StartPlayback`] and use [`ReplayRecorder`] directly instead.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use bevy::{app::AppExit, prelude::*, time::TimeUpdateStrategy};
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{
+    assets::LevelKey,
+    input::{record_actions, ActionState, ActionSystem},
+    levels::SelectedLevel,
+    rng::{seed_run, GameRng},
+};
+use crate::{args, screen::Screen};
+
+/// Bump this whenever [`Replay`] changes in a way that older replays can't be read.
+const REPLAY_VERSION: u32 = 1;
+
+pub fn plugin(app: &mut App) {
+    app.insert_resource(ReplayArgs::from_args());
+    app.observe(start_playback);
+    app.add_systems(
+        PreUpdate,
+        (
+            play_back_frame.run_if(resource_exists::<ReplayPlayback>),
+            record_frame.run_if(resource_exists::<ReplayRecorder>),
+        )
+            .chain()
+            .in_set(ActionSystem)
+            .after(record_actions),
+    );
+    // Record the seed of the run that's starting, not the previous one.
+    app.add_systems(
+        OnEnter(Screen::Playing),
+        start_replay_from_args.after(seed_run),
+    );
+    app.add_systems(OnExit(Screen::Playing), stop_recording);
+    app.add_systems(Last, stop_recording.run_if(on_event::<AppExit>()));
+}
+
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum ReplayError {
+    #[error("Could not access replay: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse replay: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("Could not write replay: {0}")]
+    Write(#[from] ron::Error),
+    #[error("Replay is from version {0}, expected version {REPLAY_VERSION}")]
+    Version(u32),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Replay {
+    version: u32,
+    /// The seed the recorded run started with.
+    pub seed: u64,
+    /// The level the recorded run was played in.
+    pub level: LevelKey,
+    pub frames: Vec<ReplayFrame>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReplayFrame {
+    /// How much real time passed since the previous frame.
+    pub delta: Duration,
+    pub actions: ActionState,
+}
+
+/// Read just the version, so replays from other versions can be told apart from broken ones.
+#[derive(Deserialize)]
+struct ReplayVersion {
+    version: u32,
+}
+
+impl Replay {
+    pub fn new(seed: u64, level: LevelKey) -> Self {
+        Self {
+            version: REPLAY_VERSION,
+            seed,
+            level,
+            frames: Vec::new(),
+        }
+    }
+
+    pub fn read(path: &Path) -> Result<Self, ReplayError> {
+        let contents = fs::read_to_string(path)?;
+        let version = ron::from_str::<ReplayVersion>(&contents)?.version;
+        if version != REPLAY_VERSION {
+            return Err(ReplayError::Version(version));
+        }
+        Ok(ron::from_str(&contents)?)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), ReplayError> {
+        let contents = ron::ser::to_string_pretty(self, PrettyConfig::default())?;
+        fs::write(path, contents)?;
+        Ok(())
+    }
+}
+
+/// Where to record runs to or replay a run from, given with `--record` and `--replay`.
+#[derive(Resource)]
+struct ReplayArgs {
+    record: Option<PathBuf>,
+    replay: Option<Replay>,
+}
+
+impl ReplayArgs {
+    fn from_args() -> Self {
+        let replay = args::value("replay").and_then(|path| {
+            Replay::read(Path::new(&path))
+                .map_err(|error| warn!("Could not replay {path:?}: {error}"))
+                .ok()
+        });
+        Self {
+            record: args::value("record").map(PathBuf::from),
+            replay,
+        }
+    }
+}
+
+/// While this resource exists, every frame is added to its replay.
+#[derive(Resource)]
+pub struct ReplayRecorder(pub Replay);
+
+/// Trigger this to reseed the [`GameRng`] and take control of
+/// the [`ActionState`] and frame times until the replay is over.
+#[derive(Event)]
+pub struct StartPlayback(pub Replay);
+
+/// A replay that is being played back.
+#[derive(Resource)]
+pub struct ReplayPlayback {
+    replay: Replay,
+    next_frame: usize,
+}
+
+/// A run condition that is `true` while recording or playing back,
+/// when things outside of the recorded actions shouldn't affect the run.
+pub fn recording_or_replaying(
+    recorder: Option<Res<ReplayRecorder>>,
+    playback: Option<Res<ReplayPlayback>>,
+) -> bool {
+    recorder.is_some() || playback.is_some()
+}
+
+fn start_playback(
+    trigger: Trigger<StartPlayback>,
+    mut rng: ResMut<GameRng>,
+    mut time_update_strategy: ResMut<TimeUpdateStrategy>,
+    mut commands: Commands,
+) {
+    let replay = trigger.event().0.clone();
+    let Some(first_frame) = replay.frames.first() else {
+        return;
+    };
+    *rng = GameRng::from_seed(replay.seed);
+    *time_update_strategy = TimeUpdateStrategy::ManualDuration(first_frame.delta);
+    commands.insert_resource(ReplayPlayback {
+        replay,
+        next_frame: 0,
+    });
+}
+
+fn start_replay_from_args(
+    args: Res<ReplayArgs>,
+    rng: Res<GameRng>,
+    selected_level: Res<SelectedLevel>,
+    mut commands: Commands,
+) {
+    if let Some(replay) = &args.replay {
+        if replay.level != selected_level.0 {
+            warn!(
+                "Not replaying, the replay is in {:?} but this run is in {:?}",
+                replay.level, selected_level.0
+            );
+            return;
+        }
+        commands.trigger(StartPlayback(replay.clone()));
+    } else if args.record.is_some() {
+        commands.insert_resource(ReplayRecorder(Replay::new(rng.seed(), selected_level.0)));
+    }
+}
+
+fn play_back_frame(
+    mut playback: ResMut<ReplayPlayback>,
+    mut actions: ResMut<ActionState>,
+    mut time_update_strategy: ResMut<TimeUpdateStrategy>,
+    mut commands: Commands,
+) {
+    let index = playback.next_frame;
+    *actions = playback.replay.frames[index].actions.clone();
+    playback.next_frame += 1;
+
+    // The time for the next frame is updated before its input is read, so set it up now.
+    match playback.replay.frames.get(index + 1) {
+        Some(next_frame) => {
+            *time_update_strategy = TimeUpdateStrategy::ManualDuration(next_frame.delta);
+        }
+        None => {
+            info!("Replay finished");
+            *time_update_strategy = TimeUpdateStrategy::Automatic;
+            commands.remove_resource::<ReplayPlayback>();
+        }
+    }
+}
+
+fn record_frame(
+    time: Res<Time<Real>>,
+    actions: Res<ActionState>,
+    mut recorder: ResMut<ReplayRecorder>,
+) {
+    recorder.0.frames.push(ReplayFrame {
+        delta: time.delta(),
+        actions: actions.clone(),
+    });
+}
+
+fn stop_recording(
+    args: Res<ReplayArgs>,
+    recorder: Option<Res<ReplayRecorder>>,
+    mut commands: Commands,
+) {
+    let (Some(path), Some(recorder)) = (&args.record, recorder) else {
+        return;
+    };
+    match recorder.0.write(path) {
+        Ok(()) => info!("Recorded replay to {path:?}"),
+        Err(error) => warn!("Could not record replay: {error}"),
+    }
+    commands.remove_resource::<ReplayRecorder>();
+}
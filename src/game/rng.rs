@@ -0,0 +1,123 @@
+//! Randomness that plays out the same way for the same run seed,
+//! for reproducing runs while debugging and for daily challenges.
+//! Every new run is seeded randomly, unless a seed is given on the command line
+//! with `--seed <number>` or `--seed daily`.
+
+use std::f32::consts::TAU;
+
+use bevy::{prelude::*, utils::SystemTime};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{args, screen::Screen};
+
+pub fn plugin(app: &mut App) {
+    app.register_type::<LaunchSeed>();
+    app.insert_resource(LaunchSeed(seed_from_args()));
+    app.insert_resource(GameRng::from_seed(0));
+    app.add_systems(OnEnter(Screen::Playing), seed_run);
+}
+
+/// The seed every run uses, if one was given at launch.
+#[derive(Resource, Reflect, Debug, Clone, Copy)]
+#[reflect(Resource)]
+pub struct LaunchSeed(pub Option<u64>);
+
+/// Kept after leaving [`Screen::Playing`] so the game over screen can show the seed.
+#[derive(Resource)]
+pub struct GameRng {
+    seed: u64,
+    gameplay: ChaCha8Rng,
+    cosmetic: ChaCha8Rng,
+}
+
+/// Where a [`GameRng`] is in its sequence, to continue it from a save.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct GameRngState {
+    seed: u64,
+    gameplay_position: u128,
+}
+
+impl GameRng {
+    /// Gameplay and cosmetics use separate streams, so that e.g. how often
+    /// footsteps are played can't change what happens in the run.
+    const GAMEPLAY_STREAM: u64 = 0;
+    const COSMETIC_STREAM: u64 = 1;
+
+    pub fn from_seed(seed: u64) -> Self {
+        let stream = |stream| {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            rng.set_stream(stream);
+            rng
+        };
+        Self {
+            seed,
+            gameplay: stream(Self::GAMEPLAY_STREAM),
+            cosmetic: stream(Self::COSMETIC_STREAM),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Randomness that affects the run, like spawns and drops.
+    pub fn gameplay(&mut self) -> &mut ChaCha8Rng {
+        &mut self.gameplay
+    }
+
+    /// Randomness that only affects how the run looks and sounds.
+    pub fn cosmetic(&mut self) -> &mut ChaCha8Rng {
+        &mut self.cosmetic
+    }
+
+    pub fn state(&self) -> GameRngState {
+        GameRngState {
+            seed: self.seed,
+            gameplay_position: self.gameplay.get_word_pos(),
+        }
+    }
+
+    pub fn from_state(state: GameRngState) -> Self {
+        let mut rng = Self::from_seed(state.seed);
+        rng.gameplay.set_word_pos(state.gameplay_position);
+        rng
+    }
+}
+
+/// A random point at most `radius` away from `center`, spread evenly over the circle.
+pub fn point_near(rng: &mut impl Rng, center: Vec2, radius: f32) -> Vec2 {
+    let angle = rng.gen_range(0.0..TAU);
+    let distance = radius * rng.gen::<f32>().sqrt();
+    center + Vec2::from_angle(angle) * distance
+}
+
+pub(super) fn seed_run(launch_seed: Res<LaunchSeed>, mut rng: ResMut<GameRng>) {
+    let seed = launch_seed.0.unwrap_or_else(|| rand::thread_rng().gen());
+    info!("Starting run with seed {seed}");
+    *rng = GameRng::from_seed(seed);
+}
+
+/// Read `--seed <seed>` from the command line.
+fn seed_from_args() -> Option<u64> {
+    let value = args::value("seed")?;
+    parse_seed(&value)
+        .map_err(|error| warn!("Ignoring seed {value:?}: {error}"))
+        .ok()
+}
+
+fn parse_seed(value: &str) -> Result<u64, std::num::ParseIntError> {
+    if value == "daily" {
+        return Ok(daily_seed());
+    }
+    value.parse()
+}
+
+/// The same seed for everyone on the same (UTC) day.
+fn daily_seed() -> u64 {
+    let since_epoch = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+    since_epoch.as_secs() / (24 * 60 * 60)
+}
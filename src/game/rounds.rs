@@ -0,0 +1,387 @@
+//! Rounds give a run its structure. Each round asks the player to meet a quota
+//! before the clock runs out, and later rounds get harder.
+
+use std::time::Duration;
+
+use bevy::{color::palettes::css::WHITE, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    enemies::Enemy, health::Died, items::CoinsEarned, plant::Harvested, spawn::player::Player,
+    stats::RunStats,
+};
+use crate::{screen::Screen, ui::prelude::*, AppSet};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_sub_state::<RoundState>();
+    app.enable_state_scoped_entities::<RoundState>();
+
+    app.register_type::<(Round, DifficultyCurve, RoundDisplay, SummaryAction)>();
+    app.init_resource::<Round>();
+    app.init_resource::<DifficultyCurve>();
+    app.observe(count_kills)
+        .observe(count_harvests)
+        .observe(count_coins);
+
+    app.add_systems(OnEnter(Screen::Playing), spawn_round_display);
+    app.add_systems(OnExit(Screen::Playing), reset_round);
+    app.add_systems(OnEnter(RoundState::Active), start_round);
+    app.add_systems(OnEnter(RoundState::Summary), (pause_time, enter_summary));
+    app.add_systems(OnExit(RoundState::Summary), unpause_time);
+    app.add_systems(
+        Update,
+        (
+            tick_round.in_set(AppSet::TickTimers),
+            (check_round_end, update_round_display)
+                .chain()
+                .in_set(AppSet::Update),
+        )
+            .run_if(in_state(RoundState::Active)),
+    );
+    app.add_systems(
+        Update,
+        handle_summary_action.run_if(in_state(RoundState::Summary)),
+    );
+}
+
+/// Whether a round is being played or its results are being shown.
+/// Entering [`RoundState::Active`] starts the next [`Round`].
+#[derive(SubStates, Debug, Hash, PartialEq, Eq, Clone, Default)]
+#[source(Screen = Screen::Playing)]
+pub enum RoundState {
+    #[default]
+    Active,
+    Summary,
+}
+
+#[derive(Reflect, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QuotaKind {
+    Coins,
+    Harvests,
+    Kills,
+}
+
+#[derive(Reflect, Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Quota {
+    pub kind: QuotaKind,
+    pub amount: u32,
+}
+
+/// What the player achieved during the current round.
+#[derive(Reflect, Default, Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct RoundStats {
+    pub coins: u32,
+    pub harvests: u32,
+    pub kills: u32,
+}
+
+impl RoundStats {
+    pub fn progress(&self, kind: QuotaKind) -> u32 {
+        match kind {
+            QuotaKind::Coins => self.coins,
+            QuotaKind::Harvests => self.harvests,
+            QuotaKind::Kills => self.kills,
+        }
+    }
+}
+
+#[derive(Reflect, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RoundOutcome {
+    /// The quota was met and there are more rounds to play.
+    Cleared,
+    /// The quota was met in the final round.
+    Won,
+    /// The clock ran out before the quota was met.
+    Failed,
+}
+
+#[derive(Resource, Reflect, Clone, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub struct Round {
+    /// Which round of the run it is, starting at 1. `0` before the first round.
+    pub number: u32,
+    pub quota: Quota,
+    pub stats: RoundStats,
+    /// Time left to meet the quota.
+    pub timer: Timer,
+    pub outcome: Option<RoundOutcome>,
+}
+
+impl Default for Round {
+    fn default() -> Self {
+        Self {
+            number: 0,
+            quota: Quota {
+                kind: QuotaKind::Kills,
+                amount: 0,
+            },
+            stats: RoundStats::default(),
+            timer: Timer::default(),
+            outcome: None,
+        }
+    }
+}
+
+impl Round {
+    pub fn quota_met(&self) -> bool {
+        self.stats.progress(self.quota.kind) >= self.quota.amount
+    }
+}
+
+/// How each round is harder than the one before.
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct DifficultyCurve {
+    /// Clearing this round wins the run.
+    pub final_round: u32,
+    pub round_duration: Duration,
+    /// Extra enemy health per round, as a fraction of the base health.
+    pub health_growth: f32,
+    /// Extra enemy speed per round, as a fraction of the base speed.
+    pub speed_growth: f32,
+    /// Every this many rounds, each hatching weed spawns another enemy.
+    pub rounds_per_extra_spawn: u32,
+}
+
+impl Default for DifficultyCurve {
+    fn default() -> Self {
+        Self {
+            final_round: 5,
+            round_duration: Duration::from_secs(90),
+            health_growth: 0.5,
+            speed_growth: 0.1,
+            rounds_per_extra_spawn: 2,
+        }
+    }
+}
+
+impl DifficultyCurve {
+    pub fn health_multiplier(&self, round: u32) -> f32 {
+        1.0 + self.health_growth * round.saturating_sub(1) as f32
+    }
+
+    pub fn speed_multiplier(&self, round: u32) -> f32 {
+        1.0 + self.speed_growth * round.saturating_sub(1) as f32
+    }
+
+    /// How many enemies hatch from a single plant.
+    pub fn spawn_count(&self, round: u32) -> u32 {
+        1 + round.saturating_sub(1) / self.rounds_per_extra_spawn.max(1)
+    }
+
+    /// The quota for a round. Rounds take turns asking for kills, harvests and coins.
+    pub fn quota(&self, round: u32) -> Quota {
+        let round = round.max(1);
+        match round % 3 {
+            1 => Quota {
+                kind: QuotaKind::Kills,
+                amount: 3 + 2 * round,
+            },
+            2 => Quota {
+                kind: QuotaKind::Harvests,
+                amount: 2 + round,
+            },
+            _ => Quota {
+                kind: QuotaKind::Coins,
+                amount: 5 * round,
+            },
+        }
+    }
+}
+
+fn reset_round(mut round: ResMut<Round>) {
+    *round = Round::default();
+}
+
+pub(super) fn start_round(mut round: ResMut<Round>, curve: Res<DifficultyCurve>) {
+    let number = round.number + 1;
+    *round = Round {
+        number,
+        quota: curve.quota(number),
+        stats: RoundStats::default(),
+        timer: Timer::new(curve.round_duration, TimerMode::Once),
+        outcome: None,
+    };
+}
+
+fn tick_round(time: Res<Time>, mut round: ResMut<Round>) {
+    round.timer.tick(time.delta());
+}
+
+fn check_round_end(
+    mut round: ResMut<Round>,
+    curve: Res<DifficultyCurve>,
+    mut next_round_state: ResMut<NextState<RoundState>>,
+) {
+    let outcome = if round.quota_met() {
+        if round.number >= curve.final_round {
+            RoundOutcome::Won
+        } else {
+            RoundOutcome::Cleared
+        }
+    } else if round.timer.finished() {
+        RoundOutcome::Failed
+    } else {
+        return;
+    };
+
+    round.outcome = Some(outcome);
+    next_round_state.set(RoundState::Summary);
+}
+
+/// Counts towards both the round and the whole run.
+/// Only kills by the player, or by a weapon they're holding, are credited.
+fn count_kills(
+    trigger: Trigger<Died>,
+    enemies: Query<(), With<Enemy>>,
+    players: Query<(), With<Player>>,
+    parents: Query<&Parent>,
+    mut round: ResMut<Round>,
+    mut run_stats: ResMut<RunStats>,
+) {
+    if !enemies.contains(trigger.entity()) {
+        return;
+    }
+    let Some(killer) = trigger.event().killer else {
+        return;
+    };
+    let by_player = players.contains(killer)
+        || parents
+            .get(killer)
+            .is_ok_and(|parent| players.contains(parent.get()));
+    if by_player {
+        round.stats.kills += 1;
+        run_stats.kills += 1;
+    }
+}
+
+fn count_harvests(
+    _trigger: Trigger<Harvested>,
+    mut round: ResMut<Round>,
+    mut run_stats: ResMut<RunStats>,
+) {
+    round.stats.harvests += 1;
+    run_stats.harvests += 1;
+}
+
+fn count_coins(
+    trigger: Trigger<CoinsEarned>,
+    mut round: ResMut<Round>,
+    mut run_stats: ResMut<RunStats>,
+) {
+    let amount = trigger.event().amount;
+    round.stats.coins += amount;
+    run_stats.coins += amount;
+}
+
+/// Keep the world still while the summary is shown.
+fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn unpause_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct RoundDisplay;
+
+fn spawn_round_display(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Round Display"),
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: default(),
+                font_size: 50.0,
+                color: WHITE.into(),
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            right: Val::Px(10.0),
+            ..default()
+        }),
+        RoundDisplay,
+        StateScoped(Screen::Playing),
+    ));
+}
+
+fn quota_name(kind: QuotaKind) -> &'static str {
+    match kind {
+        QuotaKind::Coins => "Coins",
+        QuotaKind::Harvests => "Harvests",
+        QuotaKind::Kills => "Kills",
+    }
+}
+
+fn update_round_display(round: Res<Round>, mut displays: Query<&mut Text, With<RoundDisplay>>) {
+    let remaining = round.timer.remaining_secs().ceil();
+    for mut text in &mut displays {
+        text.sections[0].value = format!(
+            "Round {} - {} {}/{} - {remaining}s",
+            round.number,
+            quota_name(round.quota.kind),
+            round.stats.progress(round.quota.kind),
+            round.quota.amount,
+        );
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum SummaryAction {
+    NextRound,
+    Title,
+}
+
+fn enter_summary(round: Res<Round>, mut commands: Commands) {
+    let header = match round.outcome {
+        Some(RoundOutcome::Won) => "You Win!".to_string(),
+        Some(RoundOutcome::Failed) => format!("Round {} Failed", round.number),
+        Some(RoundOutcome::Cleared) | None => format!("Round {} Cleared", round.number),
+    };
+
+    commands
+        .ui_root()
+        .insert((
+            Name::new("Round Summary"),
+            BackgroundColor(ui_palette::OVERLAY_BACKGROUND),
+            StateScoped(RoundState::Summary),
+        ))
+        .with_children(|children| {
+            children.header(header);
+            children.label(format!(
+                "Quota: {}/{} {}",
+                round.stats.progress(round.quota.kind),
+                round.quota.amount,
+                quota_name(round.quota.kind),
+            ));
+            children.label(format!("Kills: {}", round.stats.kills));
+            children.label(format!("Harvests: {}", round.stats.harvests));
+            children.label(format!("Coins: {}", round.stats.coins));
+            if round.outcome == Some(RoundOutcome::Cleared) {
+                children
+                    .button("Next Round")
+                    .insert(SummaryAction::NextRound);
+            }
+            children.button("Title").insert(SummaryAction::Title);
+        });
+}
+
+fn handle_summary_action(
+    mut next_round_state: ResMut<NextState<RoundState>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut button_query: InteractionQuery<&SummaryAction>,
+) {
+    for (interaction, action) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            match action {
+                SummaryAction::NextRound => next_round_state.set(RoundState::Active),
+                SummaryAction::Title => next_screen.set(Screen::Title),
+            }
+        }
+    }
+}
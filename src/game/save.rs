@@ -0,0 +1,286 @@
+//! Save the run so it can be continued from the title screen.
+//! The run is saved when the player quits to the title and whenever a round is cleared,
+//! and the save is deleted once the run is over.
+
+use bevy::prelude::*;
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    assets::LevelKey,
+    cycle::DayCycle,
+    enemies::{Enemy, EnemyArchetype},
+    health::Health,
+    items::{Item, SeedInventory, SpawnItem, Wallet},
+    plant::{Plant, RestorePlant, Soil},
+    rng::{GameRng, GameRngState},
+    rounds::{start_round, Round, RoundOutcome, RoundState},
+    spawn::{enemy::SpawnEnemy, level::Level, player::Player},
+    stats::RunStats,
+    tools::{CurrentTool, SwitchTool, ToolKind, ToolStats},
+    weapons::WeaponStats,
+};
+use crate::{screen::Screen, storage::Storage};
+
+const SAVE_KEY: &str = "save";
+
+/// Bump this whenever [`SaveFile`] changes in a way that older saves can't be read.
+const SAVE_VERSION: u32 = 1;
+
+pub(super) fn plugin(app: &mut App) {
+    app.observe(save_run);
+    app.add_systems(
+        OnEnter(RoundState::Active),
+        load_run
+            .after(start_round)
+            .run_if(resource_exists::<PendingLoad>),
+    );
+    app.add_systems(OnEnter(RoundState::Summary), save_cleared_round);
+    app.add_systems(OnEnter(Screen::GameOver), delete_save);
+}
+
+/// Trigger this to write the current run to storage.
+#[derive(Event)]
+pub struct SaveRun;
+
+/// A saved run to restore when [`Screen::Playing`] is entered.
+#[derive(Resource)]
+pub struct PendingLoad(pub SaveFile);
+
+#[derive(Serialize, Deserialize)]
+pub struct SaveFile {
+    version: u32,
+    level: LevelKey,
+    wallet: Wallet,
+    seed_inventory: SeedInventory,
+    tool_stats: ToolStats,
+    weapon_stats: WeaponStats,
+    round: Round,
+    day_cycle: DayCycle,
+    run_stats: RunStats,
+    rng: GameRngState,
+    player: SavedPlayer,
+    soil: Vec<SavedSoil>,
+    enemies: Vec<SavedEnemy>,
+    items: Vec<Vec2>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedPlayer {
+    position: Vec2,
+    health: Health,
+    tool: Option<ToolKind>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedSoil {
+    position: Vec2,
+    moisture: f32,
+    plant: Option<Plant>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedEnemy {
+    position: Vec2,
+    archetype: EnemyArchetype,
+    health: Health,
+}
+
+/// Read just the version, so saves from other versions can be told apart from broken ones.
+#[derive(Deserialize)]
+struct SaveVersion {
+    version: u32,
+}
+
+impl SaveFile {
+    /// Load the saved run, if there is one this version of the game can read.
+    pub fn load(storage: &Storage) -> Option<Self> {
+        let contents = storage.read(SAVE_KEY)?;
+        let version = ron::from_str::<SaveVersion>(&contents)
+            .map_err(|error| warn!("Could not read save: {error}"))
+            .ok()?
+            .version;
+        if version != SAVE_VERSION {
+            warn!("Ignoring save from version {version}, expected version {SAVE_VERSION}");
+            return None;
+        }
+        ron::from_str(&contents)
+            .map_err(|error| warn!("Could not read save: {error}"))
+            .ok()
+    }
+
+    fn write(&self, storage: &Storage) {
+        let result = ron::ser::to_string_pretty(self, PrettyConfig::default())
+            .map_err(|error| error.to_string())
+            .and_then(|contents| {
+                storage
+                    .write(SAVE_KEY, &contents)
+                    .map_err(|error| error.to_string())
+            });
+        if let Err(error) = result {
+            warn!("Could not save the run: {error}");
+        }
+    }
+
+    /// The level the run is played in, which has to be spawned before the save is restored.
+    pub fn level(&self) -> LevelKey {
+        self.level
+    }
+
+    pub fn delete(storage: &Storage) {
+        if let Err(error) = storage.remove(SAVE_KEY) {
+            warn!("Could not delete save: {error}");
+        }
+    }
+}
+
+fn save_run(
+    _trigger: Trigger<SaveRun>,
+    level: Res<Level>,
+    wallet: Res<Wallet>,
+    seed_inventory: Res<SeedInventory>,
+    tool_stats: Res<ToolStats>,
+    weapon_stats: Res<WeaponStats>,
+    round: Res<Round>,
+    day_cycle: Res<DayCycle>,
+    run_stats: Res<RunStats>,
+    rng: Res<GameRng>,
+    players: Query<(&Transform, &Health, Option<&CurrentTool>), With<Player>>,
+    soil: Query<(&Soil, &Transform)>,
+    plants: Query<&Plant>,
+    enemies: Query<(&Enemy, &Transform, &Health)>,
+    items: Query<&Transform, With<Item>>,
+    storage: Res<Storage>,
+) {
+    // There's nothing left to continue once the run is over.
+    if matches!(
+        round.outcome,
+        Some(RoundOutcome::Won | RoundOutcome::Failed)
+    ) {
+        return;
+    }
+    let Ok((player_transform, player_health, current_tool)) = players.get_single() else {
+        return;
+    };
+
+    SaveFile {
+        version: SAVE_VERSION,
+        level: level.key,
+        wallet: wallet.clone(),
+        seed_inventory: seed_inventory.clone(),
+        tool_stats: tool_stats.clone(),
+        weapon_stats: weapon_stats.clone(),
+        round: round.clone(),
+        day_cycle: day_cycle.clone(),
+        run_stats: run_stats.clone(),
+        rng: rng.state(),
+        player: SavedPlayer {
+            position: player_transform.translation.truncate(),
+            health: player_health.clone(),
+            tool: current_tool.map(|tool| tool.0),
+        },
+        soil: soil
+            .iter()
+            .map(|(soil, transform)| SavedSoil {
+                position: transform.translation.truncate(),
+                moisture: soil.moisture,
+                plant: soil.plant.and_then(|plant| plants.get(plant).ok()).cloned(),
+            })
+            .collect(),
+        enemies: enemies
+            .iter()
+            .map(|(enemy, transform, health)| SavedEnemy {
+                position: transform.translation.truncate(),
+                archetype: enemy.archetype,
+                health: health.clone(),
+            })
+            .collect(),
+        items: items
+            .iter()
+            .map(|transform| transform.translation.truncate())
+            .collect(),
+    }
+    .write(&storage);
+}
+
+/// Restore a saved run on top of the freshly spawned level.
+/// Runs after [`start_round`] so the saved round replaces the new one.
+fn load_run(
+    pending: Res<PendingLoad>,
+    mut wallet: ResMut<Wallet>,
+    mut seed_inventory: ResMut<SeedInventory>,
+    mut tool_stats: ResMut<ToolStats>,
+    mut weapon_stats: ResMut<WeaponStats>,
+    mut round: ResMut<Round>,
+    mut day_cycle: ResMut<DayCycle>,
+    mut run_stats: ResMut<RunStats>,
+    mut rng: ResMut<GameRng>,
+    mut players: Query<(Entity, &mut Transform, &mut Health), With<Player>>,
+    mut soil: Query<(Entity, &mut Soil, &Transform), Without<Player>>,
+    mut commands: Commands,
+) {
+    let save = &pending.0;
+    *wallet = save.wallet.clone();
+    *seed_inventory = save.seed_inventory.clone();
+    *tool_stats = save.tool_stats.clone();
+    *weapon_stats = save.weapon_stats.clone();
+    *round = Round {
+        // Let the round end again if it was already over.
+        outcome: None,
+        ..save.round.clone()
+    };
+    *day_cycle = save.day_cycle.clone();
+    *run_stats = save.run_stats.clone();
+    *rng = GameRng::from_state(save.rng);
+
+    if let Ok((player, mut transform, mut health)) = players.get_single_mut() {
+        transform.translation = save.player.position.extend(transform.translation.z);
+        *health = save.player.health.clone();
+        if let Some(tool_kind) = save.player.tool {
+            commands.trigger_targets(SwitchTool { tool_kind }, player);
+        }
+    }
+
+    // A level lays out the same soil every time, so match saved tiles by position.
+    for saved in &save.soil {
+        let Some((entity, mut soil, _)) = soil.iter_mut().find(|(_, _, transform)| {
+            transform.translation.truncate().distance(saved.position) < 1.0
+        }) else {
+            continue;
+        };
+        soil.moisture = saved.moisture;
+        if let Some(plant) = &saved.plant {
+            commands.trigger_targets(
+                RestorePlant {
+                    plant: plant.clone(),
+                },
+                entity,
+            );
+        }
+    }
+
+    for enemy in &save.enemies {
+        commands.trigger(SpawnEnemy {
+            position: enemy.position,
+            archetype: enemy.archetype,
+            health: Some(enemy.health.clone()),
+        });
+    }
+    for &position in &save.items {
+        commands.trigger(SpawnItem { position });
+    }
+
+    commands.remove_resource::<PendingLoad>();
+}
+
+/// Keep a checkpoint after each cleared round, and drop the save once the run is over.
+fn save_cleared_round(round: Res<Round>, storage: Res<Storage>, mut commands: Commands) {
+    match round.outcome {
+        Some(RoundOutcome::Cleared) => commands.trigger(SaveRun),
+        _ => SaveFile::delete(&storage),
+    }
+}
+
+fn delete_save(storage: Res<Storage>) {
+    SaveFile::delete(&storage);
+}
@@ -0,0 +1,340 @@
+//! An in-game shop overlay where coins from the [`Wallet`] buy seeds, upgrades and healing.
+//! The goods on offer are defined in `assets/shop.catalog.ron`.
+
+use bevy::{prelude::*, ui::Val::*};
+use serde::Deserialize;
+use thiserror::Error;
+
+use super::{
+    assets::{CatalogKey, HandleMap, PlantKey, RonAsset, RonAssetLoader},
+    health::Health,
+    input::{action_just_pressed, Action},
+    items::{SeedInventory, Wallet},
+    spawn::player::Player,
+    status::StatusEffect,
+    tools::{CurrentTool, SwitchTool, ToolStats},
+    weapons::WeaponStats,
+};
+use crate::{screen::Screen, ui::prelude::*};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<ShopCatalog>();
+    app.register_asset_loader(RonAssetLoader::<ShopCatalog>::new(&["catalog.ron"]));
+
+    app.add_sub_state::<ShopState>();
+    app.enable_state_scoped_entities::<ShopState>();
+
+    app.register_type::<ShopAction>();
+    app.observe(apply_purchase);
+    app.add_systems(OnEnter(ShopState::Open), enter_shop);
+    app.add_systems(
+        Update,
+        toggle_shop.run_if(in_state(Screen::Playing).and_then(action_just_pressed(Action::Shop))),
+    );
+    app.add_systems(
+        Update,
+        (update_affordability, handle_shop_action)
+            .chain()
+            .run_if(in_state(ShopState::Open)),
+    );
+}
+
+#[derive(Asset, TypePath, Debug, Deserialize)]
+pub struct ShopCatalog {
+    pub goods: Vec<Good>,
+}
+
+impl RonAsset for ShopCatalog {}
+
+#[derive(Debug, Deserialize)]
+pub struct Good {
+    pub name: String,
+    pub price: u32,
+    pub effect: GoodEffect,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub enum GoodEffect {
+    /// Add seeds to the [`SeedInventory`].
+    Seeds { species: PlantKey, amount: u32 },
+    /// Add to the damage of each weapon's bullets.
+    BulletDamage(f32),
+    /// Multiply how often each weapon fires.
+    FireRate(Multiplier),
+    /// Add to how many enemies each weapon's bullets pass through.
+    Pierce(u32),
+    /// Make each weapon's hits apply a status effect.
+    OnHit(StatusEffect),
+    /// Add to the radius of the seed planter.
+    PlanterRadius(f32),
+    /// Restore the player's health.
+    Heal(f32),
+}
+
+/// A factor that's finite and greater than zero, so it can't stop, reverse or break what it scales.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(try_from = "f32")]
+pub struct Multiplier(f32);
+
+impl Multiplier {
+    pub fn get(self) -> f32 {
+        self.0
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+#[error("A multiplier must be finite and greater than zero, but was {0}")]
+pub struct MultiplierError(f32);
+
+impl TryFrom<f32> for Multiplier {
+    type Error = MultiplierError;
+
+    fn try_from(value: f32) -> Result<Self, Self::Error> {
+        if value.is_finite() && value > 0.0 {
+            Ok(Self(value))
+        } else {
+            Err(MultiplierError(value))
+        }
+    }
+}
+
+/// Whether the shop overlay is shown on top of the game.
+#[derive(SubStates, Debug, Hash, PartialEq, Eq, Clone, Default)]
+#[source(Screen = Screen::Playing)]
+pub enum ShopState {
+    #[default]
+    Closed,
+    Open,
+}
+
+/// Trigger this to buy a good.
+/// The price is only paid if the player can afford it and the effect can be applied.
+#[derive(Event)]
+pub struct Purchase {
+    pub price: u32,
+    pub effect: GoodEffect,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum ShopAction {
+    /// Buy the good at this index in the catalog.
+    Buy(usize),
+    Close,
+}
+
+fn toggle_shop(
+    shop_state: Res<State<ShopState>>,
+    mut next_shop_state: ResMut<NextState<ShopState>>,
+) {
+    next_shop_state.set(match shop_state.get() {
+        ShopState::Closed => ShopState::Open,
+        ShopState::Open => ShopState::Closed,
+    });
+}
+
+fn enter_shop(
+    mut commands: Commands,
+    catalog_handles: Res<HandleMap<CatalogKey>>,
+    catalogs: Res<Assets<ShopCatalog>>,
+) {
+    let Some(catalog) = catalogs.get(&catalog_handles[&CatalogKey::Shop]) else {
+        return;
+    };
+
+    commands
+        .ui_root()
+        .insert((
+            Name::new("Shop"),
+            BackgroundColor(ui_palette::OVERLAY_BACKGROUND),
+            StateScoped(ShopState::Open),
+        ))
+        .with_children(|children| {
+            children.header("Shop");
+            for (index, good) in catalog.goods.iter().enumerate() {
+                children
+                    .spawn((
+                        Name::new("Good"),
+                        NodeBundle {
+                            style: Style {
+                                align_items: AlignItems::Center,
+                                flex_direction: FlexDirection::Row,
+                                column_gap: Px(10.0),
+                                ..default()
+                            },
+                            ..default()
+                        },
+                    ))
+                    .with_children(|row| {
+                        row.label(format!("{} - ${}", good.name, good.price));
+                        row.button("Buy").insert(ShopAction::Buy(index));
+                    });
+            }
+            children.button("Close").insert(ShopAction::Close);
+        });
+}
+
+/// Grey out the goods the player can't afford.
+fn update_affordability(
+    wallet: Res<Wallet>,
+    catalog_handles: Res<HandleMap<CatalogKey>>,
+    catalogs: Res<Assets<ShopCatalog>>,
+    mut buttons: Query<(
+        &ShopAction,
+        &Interaction,
+        &mut InteractionPalette,
+        &mut BackgroundColor,
+        &Children,
+    )>,
+    mut texts: Query<&mut Text>,
+) {
+    let Some(catalog) = catalogs.get(&catalog_handles[&CatalogKey::Shop]) else {
+        return;
+    };
+
+    for (action, interaction, mut palette, mut background, children) in &mut buttons {
+        let ShopAction::Buy(index) = action else {
+            continue;
+        };
+        let affordable = catalog
+            .goods
+            .get(*index)
+            .is_some_and(|good| good.price <= wallet.amount);
+        let (new_palette, text_color) = if affordable {
+            (
+                InteractionPalette {
+                    none: ui_palette::NODE_BACKGROUND,
+                    hovered: ui_palette::BUTTON_HOVERED_BACKGROUND,
+                    pressed: ui_palette::BUTTON_PRESSED_BACKGROUND,
+                },
+                ui_palette::BUTTON_TEXT,
+            )
+        } else {
+            (
+                InteractionPalette {
+                    none: ui_palette::BUTTON_DISABLED_BACKGROUND,
+                    hovered: ui_palette::BUTTON_DISABLED_BACKGROUND,
+                    pressed: ui_palette::BUTTON_DISABLED_BACKGROUND,
+                },
+                ui_palette::BUTTON_DISABLED_TEXT,
+            )
+        };
+        if palette.none == new_palette.none {
+            continue;
+        }
+
+        *background = match interaction {
+            Interaction::None => new_palette.none,
+            Interaction::Hovered => new_palette.hovered,
+            Interaction::Pressed => new_palette.pressed,
+        }
+        .into();
+        *palette = new_palette;
+        for &child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(child) {
+                for section in &mut text.sections {
+                    section.style.color = text_color;
+                }
+            }
+        }
+    }
+}
+
+fn handle_shop_action(
+    mut next_shop_state: ResMut<NextState<ShopState>>,
+    mut button_query: InteractionQuery<&ShopAction>,
+    catalog_handles: Res<HandleMap<CatalogKey>>,
+    catalogs: Res<Assets<ShopCatalog>>,
+    mut commands: Commands,
+) {
+    for (interaction, action) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            match action {
+                ShopAction::Buy(index) => {
+                    let Some(good) = catalogs
+                        .get(&catalog_handles[&CatalogKey::Shop])
+                        .and_then(|catalog| catalog.goods.get(*index))
+                    else {
+                        continue;
+                    };
+                    commands.trigger(Purchase {
+                        price: good.price,
+                        effect: good.effect.clone(),
+                    });
+                }
+                ShopAction::Close => next_shop_state.set(ShopState::Closed),
+            }
+        }
+    }
+}
+
+fn apply_purchase(
+    trigger: Trigger<Purchase>,
+    mut wallet: ResMut<Wallet>,
+    mut seed_inventory: ResMut<SeedInventory>,
+    mut tool_stats: ResMut<ToolStats>,
+    mut weapon_stats: ResMut<WeaponStats>,
+    mut players: Query<(Entity, Option<&mut Health>, Option<&CurrentTool>), With<Player>>,
+    mut commands: Commands,
+) {
+    let purchase = trigger.event();
+    if purchase.price > wallet.amount {
+        return;
+    }
+    // Only take the coins for goods that can do something, so healing needs a hurt player.
+    let Ok((player, health, current_tool)) = players.get_single_mut() else {
+        return;
+    };
+    if matches!(purchase.effect, GoodEffect::Heal(_))
+        && !health
+            .as_ref()
+            .is_some_and(|health| health.current < health.max)
+    {
+        return;
+    }
+    wallet.amount -= purchase.price;
+
+    match purchase.effect {
+        GoodEffect::Seeds { species, amount } => {
+            seed_inventory.add(species, amount);
+            return;
+        }
+        GoodEffect::Heal(amount) => {
+            if let Some(mut health) = health {
+                health.current = (health.current + amount).min(health.max);
+            }
+            return;
+        }
+        GoodEffect::BulletDamage(amount) => {
+            for weapon in weapon_stats.0.values_mut() {
+                weapon.damage += amount;
+            }
+        }
+        GoodEffect::FireRate(multiplier) => {
+            for weapon in weapon_stats.0.values_mut() {
+                weapon.fire_interval_secs /= multiplier.get();
+            }
+        }
+        GoodEffect::Pierce(amount) => {
+            for weapon in weapon_stats.0.values_mut() {
+                weapon.pierce += amount;
+            }
+        }
+        GoodEffect::OnHit(ref effect) => {
+            for weapon in weapon_stats.0.values_mut() {
+                weapon.on_hit.push(effect.clone());
+            }
+        }
+        GoodEffect::PlanterRadius(amount) => tool_stats.planter_radius += amount,
+    }
+
+    // Re-equip the current tool so it picks up the upgraded stats.
+    if let Some(current_tool) = current_tool {
+        commands.trigger_targets(
+            SwitchTool {
+                tool_kind: current_tool.0,
+            },
+            player,
+        );
+    }
+}
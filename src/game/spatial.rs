@@ -0,0 +1,81 @@
+//! A uniform grid for finding nearby entities without checking every pair.
+
+use std::marker::PhantomData;
+
+use bevy::{prelude::*, utils::HashMap};
+
+/// Adds a [`SpatialGrid<T>`] that is rebuilt from every entity with `T` at the start of each fixed step.
+pub fn spatial_grid_plugin<T: Component>(app: &mut App) {
+    app.init_resource::<SpatialGrid<T>>();
+    app.add_systems(FixedPreUpdate, rebuild_spatial_grid::<T>);
+}
+
+/// Entities with `T`, bucketed by their position at the start of the fixed step.
+/// Positions come from the `Transform`, so `T` should only be on entities without a parent.
+/// Entities may have moved since then, so check their current position when it matters.
+#[derive(Resource)]
+pub struct SpatialGrid<T> {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<(Entity, Vec2)>>,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for SpatialGrid<T> {
+    fn default() -> Self {
+        Self::new(200.0)
+    }
+}
+
+impl<T> SpatialGrid<T> {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::default(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Remove every entity, keeping the allocated cells around for reuse.
+    pub fn clear(&mut self) {
+        for cell in self.cells.values_mut() {
+            cell.clear();
+        }
+    }
+
+    pub fn insert(&mut self, entity: Entity, position: Vec2) {
+        let cell = self.cell(position);
+        self.cells.entry(cell).or_default().push((entity, position));
+    }
+
+    /// Every entity in the grid, along with its position when it was inserted.
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, Vec2)> + '_ {
+        self.cells.values().flatten().copied()
+    }
+
+    /// Every entity within `radius` of `position`, along with its position when it was inserted.
+    pub fn query(&self, position: Vec2, radius: f32) -> impl Iterator<Item = (Entity, Vec2)> + '_ {
+        let min = self.cell(position - Vec2::splat(radius));
+        let max = self.cell(position + Vec2::splat(radius));
+        (min.x..=max.x)
+            .flat_map(move |x| (min.y..=max.y).map(move |y| IVec2::new(x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+            .filter(move |(_, other)| other.distance_squared(position) <= radius * radius)
+    }
+
+    fn cell(&self, position: Vec2) -> IVec2 {
+        (position / self.cell_size).floor().as_ivec2()
+    }
+}
+
+fn rebuild_spatial_grid<T: Component>(
+    mut grid: ResMut<SpatialGrid<T>>,
+    // `GlobalTransform` is only propagated once a frame, so it lags behind the simulation.
+    entities: Query<(Entity, &Transform), With<T>>,
+) {
+    grid.clear();
+    for (entity, transform) in &entities {
+        grid.insert(entity, transform.translation.truncate());
+    }
+}
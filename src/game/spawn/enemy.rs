@@ -1,13 +1,22 @@
-use bevy::{color::palettes::css::WHITE, prelude::*};
+use std::time::Duration;
+
+use bevy::{
+    color::palettes::css::{PURPLE, SIENNA, WHITE},
+    prelude::*,
+};
 use bevy_rapier2d::prelude::*;
 
 use crate::{
     game::{
-        collision_groups::{ENEMY_GROUP, HIT_BOX_GROUP},
-        enemies::Enemy,
-        health::Health,
+        bullets::{AimMode, BulletSpawner},
+        collision_groups::{ENEMY_GROUP, HIT_BOX_GROUP, PLAYER_GROUP},
+        enemies::{Burrow, BurrowState, Enemy, EnemyArchetype, KeepDistance},
+        health::{DamageType, Health},
+        interpolation::InterpolateTranslation,
         layers,
-        movement::WrapWithinWindow,
+        movement::StayInLevel,
+        rounds::{DifficultyCurve, Round},
+        weapons::Weapon,
     },
     screen::Screen,
 };
@@ -21,25 +30,95 @@ pub(super) fn plugin(app: &mut App) {
 #[derive(Event)]
 pub struct SpawnEnemy {
     pub position: Vec2,
+    pub archetype: EnemyArchetype,
+    /// Replaces the archetype's health, e.g. when restoring a saved run.
+    pub health: Option<Health>,
+}
+
+/// The base stats of an archetype, before the [`DifficultyCurve`] is applied.
+struct ArchetypeStats {
+    name: &'static str,
+    health: f32,
+    /// Fraction of the player's base speed.
+    speed: f32,
+    contact_damage: f32,
+    /// See [`Health::resistances`].
+    resistances: &'static [(DamageType, f32)],
+    color: Color,
+    size: f32,
+}
+
+impl ArchetypeStats {
+    fn of(archetype: EnemyArchetype) -> Self {
+        match archetype {
+            EnemyArchetype::Chaser => Self {
+                name: "Chaser",
+                health: 2.0,
+                speed: 1.0,
+                contact_damage: 1.0,
+                resistances: &[],
+                color: WHITE.into(),
+                size: 150.0,
+            },
+            EnemyArchetype::Shooter => Self {
+                name: "Shooter",
+                health: 1.5,
+                speed: 0.4,
+                contact_damage: 1.0,
+                resistances: &[(DamageType::Poison, 0.5)],
+                color: PURPLE.into(),
+                size: 120.0,
+            },
+            EnemyArchetype::Burrower => Self {
+                name: "Burrower",
+                health: 3.0,
+                speed: 1.5,
+                contact_damage: 2.0,
+                // The soil shelters it from flames.
+                resistances: &[(DamageType::Fire, 0.5)],
+                color: SIENNA.into(),
+                size: 130.0,
+            },
+        }
+    }
 }
 
-fn spawn_enemey(trigger: Trigger<SpawnEnemy>, mut commands: Commands) {
-    commands.spawn((
-        Name::new("Enemy"),
+fn spawn_enemey(
+    trigger: Trigger<SpawnEnemy>,
+    round: Res<Round>,
+    curve: Res<DifficultyCurve>,
+    mut commands: Commands,
+) {
+    let event = trigger.event();
+    let archetype = event.archetype;
+    let stats = ArchetypeStats::of(archetype);
+    let mut enemy = commands.spawn((
+        Name::new(stats.name),
         Enemy {
-            max_speed: PLAYER_BASE_SPEED,
+            archetype,
+            max_speed: PLAYER_BASE_SPEED * stats.speed * curve.speed_multiplier(round.number),
+            phase_speed: 1.0,
+            contact_damage: stats.contact_damage,
         },
-        Health::full(2.0),
+        event.health.clone().unwrap_or_else(|| {
+            stats.resistances.iter().fold(
+                Health::full(stats.health * curve.health_multiplier(round.number)),
+                |health, &(damage_type, resistance)| {
+                    health.with_resistance(damage_type, resistance)
+                },
+            )
+        }),
         SpriteBundle {
             sprite: Sprite {
-                color: WHITE.into(),
+                color: stats.color,
                 ..default()
             },
-            transform: Transform::from_scale(Vec2::splat(150.0).extend(1.0))
-                .with_translation(trigger.event().position.extend(layers::ENEMIES)),
+            transform: Transform::from_scale(Vec2::splat(stats.size).extend(1.0))
+                .with_translation(event.position.extend(layers::ENEMIES)),
             ..default()
         },
-        WrapWithinWindow,
+        StayInLevel,
+        InterpolateTranslation::default(),
         Collider::cuboid(0.5, 0.5),
         RigidBody::KinematicPositionBased,
         CollisionGroups {
@@ -48,4 +127,49 @@ fn spawn_enemey(trigger: Trigger<SpawnEnemy>, mut commands: Commands) {
         },
         StateScoped(Screen::Playing),
     ));
+
+    match archetype {
+        EnemyArchetype::Chaser => {}
+        EnemyArchetype::Shooter => {
+            enemy
+                .insert(KeepDistance { distance: 600.0 })
+                .with_children(|children| {
+                    children.spawn((
+                        SpatialBundle::default(),
+                        BulletSpawner::new(
+                            Weapon {
+                                name: "Shooter".to_string(),
+                                damage: 1.0,
+                                damage_type: DamageType::Physical,
+                                bullet_speed: 800.0,
+                                bullet_radius: 15.0,
+                                bullet_time_to_live_secs: 3.0,
+                                fire_interval_secs: 1.5,
+                                projectiles: 1,
+                                spread_degrees: 0.0,
+                                pierce: 0,
+                                knockback: 0.0,
+                                on_hit: Vec::new(),
+                                charge: None,
+                            },
+                            AimMode::AutoNearest,
+                            1500.0,
+                            CollisionGroups {
+                                memberships: ENEMY_GROUP,
+                                filters: PLAYER_GROUP,
+                            },
+                        ),
+                    ));
+                });
+        }
+        EnemyArchetype::Burrower => {
+            let surface_time = Duration::from_secs(2);
+            enemy.insert(Burrow {
+                surface_time,
+                state: BurrowState::Surfaced {
+                    timer: Timer::new(surface_time, TimerMode::Once),
+                },
+            });
+        }
+    }
 }
@@ -1,26 +1,144 @@
-//! Spawn the main level by triggering other observers.
+//! Spawn a level from its [`LevelLayout`] by triggering other observers.
 
 use bevy::prelude::*;
 
-use super::{player::SpawnPlayer, soil::SpawnSoil};
+use super::{player::SpawnPlayer, soil::SpawnSoil, terrain::SpawnTerrain};
+use crate::game::{
+    assets::{HandleMap, LevelKey},
+    levels::{LevelLayout, Tile},
+};
 
 pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Level>();
     app.observe(spawn_level);
 }
 
 #[derive(Event, Debug)]
-pub struct SpawnLevel;
-
-fn spawn_level(_trigger: Trigger<SpawnLevel>, mut commands: Commands) {
-    // The only thing we have in our level is a player,
-    // but add things like walls etc. here.
-    commands.trigger(SpawnPlayer);
-
-    for x in -5..=5 {
-        for y in -5..=5 {
-            commands.trigger(SpawnSoil {
-                position: Vec2::new(x as f32 * 300.0, y as f32 * 300.0),
-            })
+pub struct SpawnLevel {
+    pub level: LevelKey,
+}
+
+/// How far entities are kept from the edges of solid tiles,
+/// since only their centers are checked against them.
+const SOLID_PADDING: f32 = 80.0;
+
+/// Options of the level being played, inserted when it's spawned.
+#[derive(Resource, Reflect, Clone, Debug)]
+#[reflect(Resource)]
+pub struct Level {
+    pub key: LevelKey,
+    /// The area the camera is kept within.
+    pub bounds: Rect,
+    /// How many world units one pixel of the window shows.
+    pub camera_scale: f32,
+    /// Whether entities that leave the screen come back on the other side.
+    /// Otherwise they're kept within [`Level::bounds`].
+    pub wrap: bool,
+    /// Areas that entities can't move into, like walls and water.
+    /// Neighbouring tiles are merged, but the padded areas can still overlap.
+    pub solid: Vec<Rect>,
+}
+
+impl Level {
+    /// The closest point to `position` that isn't inside any solid area,
+    /// or `None` if it isn't inside one to begin with.
+    /// Leaving one area the shortest way can lead into another,
+    /// so the way out is picked from the edges of every area involved.
+    pub fn push_out_of_solid(&self, position: Vec2) -> Option<Vec2> {
+        let inside =
+            |point: Vec2, area: &Rect| point.cmpgt(area.min).all() && point.cmplt(area.max).all();
+        let mut involved: Vec<&Rect> = self
+            .solid
+            .iter()
+            .filter(|area| inside(position, area))
+            .collect();
+        if involved.is_empty() {
+            return None;
+        }
+
+        // Each pass either finds a way out or involves another area, so this ends.
+        loop {
+            let xs: Vec<f32> = involved
+                .iter()
+                .flat_map(|area| [area.min.x, area.max.x])
+                .chain([position.x])
+                .collect();
+            let ys: Vec<f32> = involved
+                .iter()
+                .flat_map(|area| [area.min.y, area.max.y])
+                .chain([position.y])
+                .collect();
+            let mut candidates: Vec<Vec2> = xs
+                .iter()
+                .flat_map(|&x| ys.iter().map(move |&y| Vec2::new(x, y)))
+                .collect();
+            candidates.sort_by(|a, b| {
+                a.distance_squared(position)
+                    .total_cmp(&b.distance_squared(position))
+            });
+
+            let mut blocked_by = Vec::new();
+            for candidate in candidates {
+                let mut blocked = false;
+                for area in self.solid.iter().filter(|area| inside(candidate, area)) {
+                    blocked = true;
+                    if !involved.contains(&area) && !blocked_by.contains(&area) {
+                        blocked_by.push(area);
+                    }
+                }
+                if !blocked {
+                    return Some(candidate);
+                }
+            }
+            if blocked_by.is_empty() {
+                return None;
+            }
+            involved.extend(blocked_by);
         }
     }
 }
+
+fn spawn_level(
+    trigger: Trigger<SpawnLevel>,
+    level_handles: Res<HandleMap<LevelKey>>,
+    layouts: Res<Assets<LevelLayout>>,
+    mut commands: Commands,
+) {
+    let key = trigger.event().level;
+    let Some(layout) = level_handles
+        .get(&key)
+        .and_then(|handle| layouts.get(handle))
+    else {
+        warn!("Level {key:?} isn't loaded");
+        return;
+    };
+
+    for (position, tile) in layout.tiles() {
+        match tile {
+            Tile::Soil => commands.trigger(SpawnSoil { position }),
+            Tile::Spawn => {
+                commands.trigger(SpawnSoil { position });
+                commands.trigger(SpawnPlayer { position });
+            }
+            Tile::Path | Tile::Water | Tile::Wall => {
+                commands.trigger(SpawnTerrain {
+                    tile,
+                    position,
+                    size: layout.tile_size,
+                });
+            }
+        }
+    }
+
+    commands.insert_resource(Level {
+        key,
+        bounds: layout.bounds(),
+        camera_scale: layout.camera_scale,
+        wrap: layout.wrap,
+        solid: layout
+            .solid_areas()
+            .into_iter()
+            .map(|area| area.inflate(SOLID_PADDING))
+            .collect(),
+    });
+}
@@ -8,7 +8,14 @@ pub mod enemy;
 pub mod level;
 pub mod player;
 pub mod soil;
+pub mod terrain;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins((level::plugin, player::plugin, soil::plugin, enemy::plugin));
+    app.add_plugins((
+        level::plugin,
+        player::plugin,
+        soil::plugin,
+        terrain::plugin,
+        enemy::plugin,
+    ));
 }
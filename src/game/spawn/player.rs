@@ -12,8 +12,10 @@ use crate::{
         bullets::BulletSpawner,
         collision_groups::{ENEMY_GROUP, HIT_BOX_GROUP, PLAYER_GROUP},
         ghost::SpawnedGhost,
+        health::{Health, Invulnerability},
+        interpolation::InterpolateTranslation,
         layers,
-        movement::{DashSettings, MovementController, MovementSettings, WrapWithinWindow},
+        movement::{DashSettings, Facing, MovementController, MovementSettings, StayInLevel},
     },
     screen::Screen,
 };
@@ -24,16 +26,19 @@ pub(super) fn plugin(app: &mut App) {
 }
 
 #[derive(Event, Debug)]
-pub struct SpawnPlayer;
+pub struct SpawnPlayer {
+    pub position: Vec2,
+}
 
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
 #[reflect(Component)]
 pub struct Player;
 
 pub const PLAYER_BASE_SPEED: f32 = 800.0;
+pub const PLAYER_MAX_HEALTH: f32 = 10.0;
 
 fn spawn_player(
-    _trigger: Trigger<SpawnPlayer>,
+    trigger: Trigger<SpawnPlayer>,
     mut commands: Commands,
     image_handles: Res<HandleMap<ImageKey>>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
@@ -58,7 +63,7 @@ fn spawn_player(
                 },
                 texture: image_handles[&ImageKey::Ducky].clone_weak(),
                 transform: Transform::from_scale(Vec2::splat(8.0).extend(1.0))
-                    .with_translation(Vec2::ZERO.extend(layers::PLAYER)),
+                    .with_translation(trigger.event().position.extend(layers::PLAYER)),
                 ..Default::default()
             },
             TextureAtlas {
@@ -66,6 +71,7 @@ fn spawn_player(
                 index: player_animation.get_atlas_index(),
             },
             MovementController::default(),
+            Facing::default(),
             MovementSettings {
                 max_speed: base_speed,
             },
@@ -74,10 +80,15 @@ fn spawn_player(
                 distance: base_speed,
                 time: Duration::from_millis(250),
             },
-            WrapWithinWindow,
+            StayInLevel,
+            InterpolateTranslation::default(),
+            Health::full(PLAYER_MAX_HEALTH),
+            Invulnerability::new(Duration::from_secs(1)),
             player_animation,
-            RigidBody::KinematicPositionBased,
-            Collider::round_cuboid(6.0, 8.0, 50.0),
+            (
+                RigidBody::KinematicPositionBased,
+                Collider::round_cuboid(6.0, 8.0, 50.0),
+            ),
             StateScoped(Screen::Playing),
         ))
         .insert(CollisionGroups {
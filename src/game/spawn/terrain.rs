@@ -0,0 +1,66 @@
+//! Spawn the level tiles that aren't soil.
+
+use bevy::{
+    color::palettes::css::{DIM_GRAY, STEEL_BLUE, TAN},
+    prelude::*,
+};
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    game::{collision_groups::OBSTACLE_GROUP, layers, levels::Tile},
+    screen::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.observe(spawn_terrain);
+    app.register_type::<Wall>();
+}
+
+#[derive(Event, Debug)]
+pub struct SpawnTerrain {
+    pub tile: Tile,
+    pub position: Vec2,
+    pub size: f32,
+}
+
+/// Solid terrain that stops bullets.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Wall;
+
+fn spawn_terrain(trigger: Trigger<SpawnTerrain>, mut commands: Commands) {
+    let event = trigger.event();
+    let (name, color, layer) = match event.tile {
+        Tile::Path => ("Path", TAN, layers::TERRAIN),
+        Tile::Water => ("Water", STEEL_BLUE, layers::TERRAIN),
+        Tile::Wall => ("Wall", DIM_GRAY, layers::WALLS),
+        Tile::Soil | Tile::Spawn => {
+            warn!("{:?} isn't terrain, spawn it as soil instead", event.tile);
+            return;
+        }
+    };
+    let mut terrain = commands.spawn((
+        Name::new(name),
+        SpriteBundle {
+            sprite: Sprite {
+                color: color.into(),
+                ..default()
+            },
+            transform: Transform::from_scale(Vec2::splat(event.size).extend(1.0))
+                .with_translation(event.position.extend(layer)),
+            ..default()
+        },
+        StateScoped(Screen::Playing),
+    ));
+    if event.tile == Tile::Wall {
+        terrain.insert((
+            Wall,
+            Collider::cuboid(0.5, 0.5),
+            RigidBody::Fixed,
+            CollisionGroups {
+                memberships: OBSTACLE_GROUP,
+                filters: Group::all(),
+            },
+        ));
+    }
+}
@@ -0,0 +1,90 @@
+//! Plant species definitions, loaded from `.plant.ron` files.
+//! Tuning an existing crop only takes editing its file. Adding a new one takes writing a file
+//! in `assets/plants/`, adding a [`PlantKey`](super::assets::PlantKey) variant for it,
+//! listing it in [`PlantKey::ALL`](super::assets::PlantKey::ALL) and registering it in
+//! [`HandleMap<PlantKey>`](super::assets::HandleMap) and `PLANT_FILES` in `test_support.rs`.
+
+use bevy::{asset::LoadContext, prelude::*};
+use serde::Deserialize;
+
+use super::{
+    assets::{RonAsset, RonAssetLoader},
+    enemies::EnemyArchetype,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<PlantSpecies>();
+    app.register_asset_loader(RonAssetLoader::<PlantSpecies>::new(&["plant.ron"]));
+}
+
+#[derive(Asset, TypePath, Debug, Deserialize)]
+pub struct PlantSpecies {
+    pub name: String,
+    /// The stages the plant grows through, in order.
+    pub stages: Vec<GrowthStage>,
+    /// Seconds of night the fully grown plant can be harvested before it matures.
+    pub ripening_secs: f32,
+    /// What the player gets for harvesting the plant while it ripens.
+    pub harvest: HarvestYield,
+    /// What the plant turns into if it isn't harvested in time.
+    pub matures_into: MaturesInto,
+}
+
+impl RonAsset for PlantSpecies {
+    fn load_dependencies(&mut self, load_context: &mut LoadContext) {
+        for stage in &mut self.stages {
+            if let Some(path) = &stage.look.image_path {
+                stage.look.image = load_context.load(path);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GrowthStage {
+    /// Seconds of growth needed to finish this stage in soaked soil.
+    /// Drier soil makes the stage take longer.
+    pub duration_secs: f32,
+    /// The minimum soil moisture, from `0.0` to `1.0`, this stage needs to grow.
+    pub water_needed: f32,
+    pub look: StageLook,
+}
+
+/// How the plant looks during a stage.
+/// Stages without an image are drawn as colored rectangles.
+#[derive(Debug, Deserialize)]
+pub struct StageLook {
+    /// The color of the rectangle, or the tint of the image.
+    pub color: Color,
+    /// Size relative to the soil tile the plant grows in. Plants are children of their soil,
+    /// which is scaled to the tile's size, so `(1.0, 1.0)` covers the whole tile.
+    pub size: Vec2,
+    /// The image to draw, relative to the `assets` folder.
+    #[serde(default, rename = "image")]
+    pub image_path: Option<String>,
+    /// Loaded from [`StageLook::image_path`], or the default white image without one.
+    #[serde(skip)]
+    pub image: Handle<Image>,
+}
+
+impl StageLook {
+    pub fn apply(&self, sprite: &mut Sprite, image: &mut Handle<Image>) {
+        sprite.color = self.color;
+        sprite.custom_size = Some(self.size);
+        *image = self.image.clone();
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HarvestYield {
+    pub coins: u32,
+    /// Seeds of the same species.
+    pub seeds: u32,
+}
+
+#[derive(Debug, Deserialize)]
+pub enum MaturesInto {
+    Enemy { archetype: EnemyArchetype },
+    Item { amount: u32 },
+    Nothing,
+}
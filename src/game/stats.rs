@@ -0,0 +1,46 @@
+//! Statistics about the current run, shown when it ends.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{cycle::DayCycle, rounds::Round};
+use crate::screen::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<RunStats>();
+    app.init_resource::<RunStats>();
+    app.add_systems(OnEnter(Screen::Playing), reset_run_stats);
+    app.add_systems(Update, track_progress.run_if(in_state(Screen::Playing)));
+}
+
+/// Kept after leaving [`Screen::Playing`] so the game over screen can show it.
+/// Kills, harvests and coins are counted along with the [`Round`]'s.
+#[derive(Resource, Reflect, Default, Clone, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub struct RunStats {
+    /// The round the run reached.
+    pub round: u32,
+    /// The day the run reached.
+    pub day: u32,
+    pub time: Duration,
+    pub kills: u32,
+    pub harvests: u32,
+    pub coins: u32,
+}
+
+fn reset_run_stats(mut stats: ResMut<RunStats>) {
+    *stats = RunStats::default();
+}
+
+fn track_progress(
+    time: Res<Time>,
+    round: Res<Round>,
+    day_cycle: Res<DayCycle>,
+    mut stats: ResMut<RunStats>,
+) {
+    stats.time += time.delta();
+    stats.round = round.number;
+    stats.day = day_cycle.day;
+}
@@ -0,0 +1,268 @@
+//! Status effects that linger on entities with [`Health`] after a hit.
+//! Trigger [`ApplyStatus`] on an entity to give it an effect. Burn and poison hurt it through [`Damage`]
+//! every tick, slow lowers its speed, and stun stops it from steering until the effect wears off.
+
+use bevy::{
+    color::palettes::css::{ORANGE_RED, PURPLE, SKY_BLUE, YELLOW},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use super::health::{Damage, DamageType, Health};
+
+pub fn plugin(app: &mut App) {
+    app.register_type::<StatusEffects>();
+    app.observe(apply_status);
+    app.add_systems(FixedUpdate, tick_status_effects);
+    app.add_systems(Update, tint_affected_sprites);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+pub enum StatusKind {
+    Slow,
+    Burn,
+    Poison,
+    Stun,
+}
+
+impl StatusKind {
+    /// The color sprites are tinted with while the effect is active.
+    fn tint(self) -> Color {
+        match self {
+            StatusKind::Slow => SKY_BLUE.into(),
+            StatusKind::Burn => ORANGE_RED.into(),
+            StatusKind::Poison => PURPLE.into(),
+            StatusKind::Stun => YELLOW.into(),
+        }
+    }
+}
+
+/// What happens when an effect is applied to an entity that already has one of the same kind.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub enum Stacking {
+    /// Restart the existing effect with the new one's duration and strength.
+    Refresh,
+    /// Add the new effect alongside the existing ones, up to `max` at once.
+    /// Past that, the one closest to wearing off is replaced.
+    Stack { max: u32 },
+}
+
+#[derive(Clone, Debug, PartialEq, Reflect, Serialize, Deserialize)]
+pub struct StatusEffect {
+    pub kind: StatusKind,
+    pub duration_secs: f32,
+    /// Damage per tick for burn and poison, and the fraction of speed taken away for slow.
+    /// Stun ignores it.
+    pub strength: f32,
+    /// Seconds between the damage ticks of burn and poison.
+    pub tick_interval_secs: f32,
+    pub stacking: Stacking,
+}
+
+/// Trigger this on an entity with [`Health`] to give it a status effect.
+#[derive(Event)]
+pub struct ApplyStatus {
+    pub effect: StatusEffect,
+    /// The entity that applied the effect, credited with its damage.
+    pub source: Option<Entity>,
+}
+
+/// The status effects currently on an entity.
+/// Removed again once the last effect wears off.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct StatusEffects {
+    active: Vec<ActiveStatus>,
+    /// The sprite color from before it was tinted, to restore afterwards.
+    untinted: Option<Color>,
+}
+
+#[derive(Reflect)]
+struct ActiveStatus {
+    effect: StatusEffect,
+    source: Option<Entity>,
+    remaining: Timer,
+    tick: Timer,
+}
+
+/// The shortest time between damage ticks.
+/// Effects come from asset files, and a zero interval would tick endlessly in a single frame.
+const MIN_TICK_INTERVAL_SECS: f32 = 0.05;
+
+impl ActiveStatus {
+    fn new(effect: StatusEffect, source: Option<Entity>) -> Self {
+        Self {
+            remaining: Self::remaining_timer(&effect),
+            tick: Self::tick_timer(&effect),
+            effect,
+            source,
+        }
+    }
+
+    /// Negative durations would panic, so they wear off right away instead.
+    fn remaining_timer(effect: &StatusEffect) -> Timer {
+        Timer::from_seconds(effect.duration_secs.max(0.0), TimerMode::Once)
+    }
+
+    fn tick_timer(effect: &StatusEffect) -> Timer {
+        Timer::from_seconds(
+            effect.tick_interval_secs.max(MIN_TICK_INTERVAL_SECS),
+            TimerMode::Repeating,
+        )
+    }
+}
+
+impl StatusEffects {
+    pub fn has(&self, kind: StatusKind) -> bool {
+        self.active.iter().any(|status| status.effect.kind == kind)
+    }
+
+    pub fn is_stunned(&self) -> bool {
+        self.has(StatusKind::Stun)
+    }
+
+    /// How much of its normal speed the entity moves at.
+    /// Slows multiply together, and stun stops the entity completely.
+    pub fn speed_multiplier(&self) -> f32 {
+        if self.is_stunned() {
+            return 0.0;
+        }
+        self.active
+            .iter()
+            .filter(|status| status.effect.kind == StatusKind::Slow)
+            .map(|status| (1.0 - status.effect.strength).clamp(0.0, 1.0))
+            .product()
+    }
+
+    fn add(&mut self, effect: StatusEffect, source: Option<Entity>) {
+        let kind = effect.kind;
+        match effect.stacking {
+            Stacking::Refresh => {
+                if let Some(existing) = self
+                    .active
+                    .iter_mut()
+                    .find(|status| status.effect.kind == kind)
+                {
+                    existing.remaining = ActiveStatus::remaining_timer(&effect);
+                    existing.tick = ActiveStatus::tick_timer(&effect);
+                    existing.effect = effect;
+                    existing.source = source;
+                } else {
+                    self.active.push(ActiveStatus::new(effect, source));
+                }
+            }
+            Stacking::Stack { max } => {
+                let stacks = self
+                    .active
+                    .iter()
+                    .filter(|status| status.effect.kind == kind)
+                    .count();
+                if stacks < max as usize {
+                    self.active.push(ActiveStatus::new(effect, source));
+                } else if let Some(oldest) = self
+                    .active
+                    .iter_mut()
+                    .filter(|status| status.effect.kind == kind)
+                    .min_by_key(|status| status.remaining.remaining())
+                {
+                    *oldest = ActiveStatus::new(effect, source);
+                }
+            }
+        }
+    }
+
+    /// The tint of the most recently applied effect.
+    fn tint(&self) -> Option<Color> {
+        self.active.last().map(|status| status.effect.kind.tint())
+    }
+}
+
+fn apply_status(
+    trigger: Trigger<ApplyStatus>,
+    mut targets: Query<Option<&mut StatusEffects>, With<Health>>,
+    mut commands: Commands,
+) {
+    let Ok(status_effects) = targets.get_mut(trigger.entity()) else {
+        return;
+    };
+    let ApplyStatus { effect, source } = trigger.event();
+    match status_effects {
+        Some(mut status_effects) => status_effects.add(effect.clone(), *source),
+        None => {
+            // Other effects may land before the component is inserted,
+            // so add to whichever `StatusEffects` is there by then.
+            let entity = trigger.entity();
+            let (effect, source) = (effect.clone(), *source);
+            commands.add(move |world: &mut World| {
+                // The entity may have been despawned by the hit that applied the effect.
+                let Some(mut entity) = world.get_entity_mut(entity) else {
+                    return;
+                };
+                match entity.get_mut::<StatusEffects>() {
+                    Some(mut status_effects) => status_effects.add(effect, source),
+                    None => {
+                        let mut status_effects = StatusEffects::default();
+                        status_effects.add(effect, source);
+                        entity.insert(status_effects);
+                    }
+                }
+            });
+        }
+    }
+}
+
+fn tick_status_effects(
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut StatusEffects)>,
+    mut commands: Commands,
+) {
+    for (entity, mut status_effects) in &mut query {
+        for status in &mut status_effects.active {
+            status.remaining.tick(time.delta());
+            let damage_type = match status.effect.kind {
+                StatusKind::Burn => DamageType::Fire,
+                StatusKind::Poison => DamageType::Poison,
+                StatusKind::Slow | StatusKind::Stun => continue,
+            };
+            status.tick.tick(time.delta());
+            for _ in 0..status.tick.times_finished_this_tick() {
+                commands.trigger_targets(
+                    Damage {
+                        amount: status.effect.strength,
+                        damage_type,
+                        source: status.source,
+                    },
+                    entity,
+                );
+            }
+        }
+        status_effects
+            .active
+            .retain(|status| !status.remaining.finished());
+    }
+}
+
+/// Tint affected sprites, and put their color back once every effect wore off.
+fn tint_affected_sprites(
+    mut query: Query<(Entity, &mut StatusEffects, Option<&mut Sprite>)>,
+    mut commands: Commands,
+) {
+    for (entity, mut status_effects, sprite) in &mut query {
+        let Some(mut sprite) = sprite else {
+            if status_effects.active.is_empty() {
+                commands.entity(entity).remove::<StatusEffects>();
+            }
+            continue;
+        };
+        let untinted = *status_effects.untinted.get_or_insert(sprite.color);
+        // Keep the alpha, which blinks while the entity is invulnerable.
+        let alpha = sprite.color.alpha();
+        match status_effects.tint() {
+            Some(tint) => sprite.color = untinted.mix(&tint, 0.5).with_alpha(alpha),
+            None => {
+                sprite.color = untinted.with_alpha(alpha);
+                commands.entity(entity).remove::<StatusEffects>();
+            }
+        }
+    }
+}
@@ -1,25 +1,39 @@
-use std::time::Duration;
-
 use bevy::{
-    color::palettes::css::{BLUE, GREEN},
+    color::palettes::css::{BLUE, GREEN, ORANGE},
     prelude::*,
     render::mesh::CircleMeshBuilder,
     sprite::Mesh2dHandle,
 };
 
 use bevy_rapier2d::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use super::{
+    assets::WeaponKey,
     bullets::BulletSpawner,
     collision_groups::{ENEMY_GROUP, HIT_BOX_GROUP, SOIL_GROUP},
-    plant::{Planter, Water},
+    input::{Action, ActionState},
+    items::SeedInventory,
+    plant::{Harvester, Planter, Water},
     spawn::player::Player,
+    weapons::WeaponStats,
 };
+use crate::{screen::Screen, settings::Settings};
 
 pub(super) fn plugin(app: &mut App) {
+    app.register_type::<(CurrentTool, ToolStats)>();
+    app.init_resource::<ToolStats>();
     app.observe(switch_tool);
     app.add_systems(Startup, init_tool_assets);
-    app.add_systems(Update, controls);
+    app.add_systems(OnEnter(Screen::Playing), reset_tool_stats);
+    app.add_systems(
+        Update,
+        (
+            controls,
+            seed_controls,
+            apply_aim_mode.run_if(resource_changed::<Settings>),
+        ),
+    );
 }
 
 #[derive(Event)]
@@ -27,10 +41,54 @@ pub struct SwitchTool {
     pub tool_kind: ToolKind,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Reflect, Serialize, Deserialize)]
 pub enum ToolKind {
     SeedPlanter,
-    Gun,
+    Weapon(WeaponKey),
     Water,
+    Harvester,
+}
+
+impl ToolKind {
+    /// Every tool, in the order they are selected with [`Action::SelectTool`].
+    pub const ALL: [ToolKind; 7] = [
+        ToolKind::SeedPlanter,
+        ToolKind::Water,
+        ToolKind::Weapon(WeaponKey::Pistol),
+        ToolKind::Harvester,
+        ToolKind::Weapon(WeaponKey::Shotgun),
+        ToolKind::Weapon(WeaponKey::Lance),
+        ToolKind::Weapon(WeaponKey::Cannon),
+    ];
+}
+
+/// The tool the player is holding.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct CurrentTool(pub ToolKind);
+
+/// Tool stats that can be upgraded during a run.
+/// Tools read these whenever they are equipped.
+/// Weapons have their own stats in [`WeaponStats`].
+#[derive(Resource, Reflect, Clone, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub struct ToolStats {
+    pub planter_radius: f32,
+}
+
+/// How far away weapons aim at enemies automatically.
+const WEAPON_RANGE: f32 = 2000.0;
+
+impl Default for ToolStats {
+    fn default() -> Self {
+        Self {
+            planter_radius: 25.0,
+        }
+    }
+}
+
+fn reset_tool_stats(mut tool_stats: ResMut<ToolStats>) {
+    *tool_stats = ToolStats::default();
 }
 
 #[derive(Resource)]
@@ -38,6 +96,7 @@ struct ToolAssets {
     circle_mesh: Mesh2dHandle,
     planter_material: Handle<ColorMaterial>,
     water_material: Handle<ColorMaterial>,
+    harvester_material: Handle<ColorMaterial>,
 }
 
 fn init_tool_assets(
@@ -49,24 +108,32 @@ fn init_tool_assets(
         circle_mesh: Mesh2dHandle(meshes.add(CircleMeshBuilder::new(0.5, 100).build())),
         planter_material: color_materials.add(ColorMaterial::from_color(GREEN.with_alpha(0.3))),
         water_material: color_materials.add(ColorMaterial::from_color(BLUE.with_alpha(0.3))),
+        harvester_material: color_materials.add(ColorMaterial::from_color(ORANGE.with_alpha(0.3))),
     });
 }
 
 fn controls(
-    input: Res<ButtonInput<KeyCode>>,
-    players: Query<Entity, With<Player>>,
+    actions: Res<ActionState>,
+    players: Query<(Entity, Option<&CurrentTool>), With<Player>>,
     mut commands: Commands,
 ) {
-    let Ok(player) = players.get_single() else {
+    let Ok((player, current_tool)) = players.get_single() else {
         return;
     };
 
-    let tool_kind = if input.just_pressed(KeyCode::Digit1) {
-        ToolKind::SeedPlanter
-    } else if input.just_pressed(KeyCode::Digit2) {
-        ToolKind::Water
-    } else if input.just_pressed(KeyCode::Digit3) {
-        ToolKind::Gun
+    let selected =
+        (0..ToolKind::ALL.len()).find(|&index| actions.just_pressed(Action::SelectTool(index)));
+    let tool_kind = if let Some(index) = selected {
+        ToolKind::ALL[index]
+    } else if actions.just_pressed(Action::NextTool) {
+        let index = current_tool
+            .and_then(|current_tool| {
+                ToolKind::ALL
+                    .iter()
+                    .position(|&tool| tool == current_tool.0)
+            })
+            .map_or(0, |index| (index + 1) % ToolKind::ALL.len());
+        ToolKind::ALL[index]
     } else {
         return;
     };
@@ -74,36 +141,52 @@ fn controls(
     commands.trigger_targets(SwitchTool { tool_kind }, player);
 }
 
+fn seed_controls(actions: Res<ActionState>, mut seed_inventory: ResMut<SeedInventory>) {
+    if actions.just_pressed(Action::NextSeed) {
+        seed_inventory.cycle();
+    }
+}
+
 fn switch_tool(
     trigger: Trigger<SwitchTool>,
     planter_assets: Res<ToolAssets>,
+    tool_stats: Res<ToolStats>,
+    weapon_stats: Res<WeaponStats>,
+    settings: Res<Settings>,
     mut commands: Commands,
 ) {
     commands
         .entity(trigger.entity())
         .despawn_descendants()
+        .insert(CurrentTool(trigger.event().tool_kind))
         .with_children(|children| {
             match trigger.event().tool_kind {
-                ToolKind::Gun => children.spawn((
-                    SpatialBundle::default(),
-                    BulletSpawner {
-                        bullet_damage: 1.0,
-                        bullet_speed: 2000.0,
-                        timer: Timer::new(Duration::from_millis(100), TimerMode::Repeating),
-                        bullet_time_to_live: Duration::from_secs(5),
-                        bullet_radius: 25.0,
-                        collision_groups: CollisionGroups {
-                            memberships: HIT_BOX_GROUP,
-                            filters: ENEMY_GROUP,
-                        },
-                    },
-                )),
+                ToolKind::Weapon(weapon_key) => {
+                    let Some(weapon) = weapon_stats.0.get(&weapon_key) else {
+                        warn!("Weapon {weapon_key:?} isn't loaded");
+                        return;
+                    };
+                    children.spawn((
+                        SpatialBundle::default(),
+                        BulletSpawner::new(
+                            weapon.clone(),
+                            settings.aim_mode,
+                            WEAPON_RANGE,
+                            CollisionGroups {
+                                memberships: HIT_BOX_GROUP,
+                                filters: ENEMY_GROUP,
+                            },
+                        ),
+                    ))
+                }
                 ToolKind::SeedPlanter => children.spawn((
                     ColorMesh2dBundle {
                         mesh: planter_assets.circle_mesh.clone(),
                         material: planter_assets.planter_material.clone(),
-                        transform: Transform::from_scale(Vec2::splat(50.0).extend(1.0))
-                            .with_translation(Vec2::ZERO.extend(-0.01)),
+                        transform: Transform::from_scale(
+                            Vec2::splat(tool_stats.planter_radius * 2.0).extend(1.0),
+                        )
+                        .with_translation(Vec2::ZERO.extend(-0.01)),
                         ..default()
                     },
                     Planter,
@@ -130,6 +213,36 @@ fn switch_tool(
                         filters: SOIL_GROUP,
                     },
                 )),
+                ToolKind::Harvester => children.spawn((
+                    ColorMesh2dBundle {
+                        mesh: planter_assets.circle_mesh.clone(),
+                        material: planter_assets.harvester_material.clone(),
+                        transform: Transform::from_scale(Vec2::splat(50.0).extend(1.0))
+                            .with_translation(Vec2::ZERO.extend(-0.01)),
+                        ..default()
+                    },
+                    Harvester,
+                    Sensor,
+                    Collider::ball(0.5),
+                    CollisionGroups {
+                        memberships: Group::all(),
+                        filters: SOIL_GROUP,
+                    },
+                )),
             };
         });
 }
+
+/// Aim a weapon the player is already holding the new way when the setting changes.
+fn apply_aim_mode(
+    settings: Res<Settings>,
+    players: Query<&Children, With<Player>>,
+    mut spawners: Query<&mut BulletSpawner>,
+) {
+    for children in &players {
+        let mut weapons = spawners.iter_many_mut(children);
+        while let Some(mut weapon) = weapons.fetch_next() {
+            weapon.aim = settings.aim_mode;
+        }
+    }
+}
@@ -0,0 +1,106 @@
+//! Weapon definitions, loaded from `.weapon.ron` files.
+//! Each weapon is a tool the player can equip, and fires through a [`BulletSpawner`](super::bullets::BulletSpawner).
+//! Add a new weapon by writing a file in `assets/weapons/` and
+//! registering it in [`HandleMap<WeaponKey>`](super::assets::HandleMap) and [`ToolKind::ALL`](super::tools::ToolKind::ALL).
+
+use std::time::Duration;
+
+use bevy::{prelude::*, utils::HashMap};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    assets::{HandleMap, RonAsset, RonAssetLoader, WeaponKey},
+    health::DamageType,
+    status::StatusEffect,
+};
+use crate::screen::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<Weapon>();
+    app.register_asset_loader(RonAssetLoader::<Weapon>::new(&["weapon.ron"]));
+
+    app.register_type::<WeaponStats>();
+    app.init_resource::<WeaponStats>();
+    app.add_systems(OnEnter(Screen::Playing), reset_weapon_stats);
+}
+
+#[derive(Asset, Reflect, Debug, Clone, Serialize, Deserialize)]
+pub struct Weapon {
+    pub name: String,
+    /// Damage per bullet.
+    pub damage: f32,
+    pub damage_type: DamageType,
+    pub bullet_speed: f32,
+    pub bullet_radius: f32,
+    pub bullet_time_to_live_secs: f32,
+    /// Seconds between shots. Charged weapons can't start charging again any sooner.
+    pub fire_interval_secs: f32,
+    /// Bullets fired per shot, fanned out evenly across the spread.
+    pub projectiles: u32,
+    /// The angle between the outermost bullets of a shot.
+    pub spread_degrees: f32,
+    /// How many enemies a bullet passes through. With `0`, it stops at the first one.
+    pub pierce: u32,
+    /// How fast a hit pushes the target away.
+    pub knockback: f32,
+    /// Status effects each hit applies to the target.
+    pub on_hit: Vec<StatusEffect>,
+    /// Hold [`Action::Use`](super::input::Action::Use) to charge a shot instead of firing automatically.
+    pub charge: Option<Charge>,
+}
+
+impl RonAsset for Weapon {}
+
+#[derive(Reflect, Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Charge {
+    /// Seconds of charging to reach full damage.
+    pub time_secs: f32,
+    /// Damage multiplier of a fully charged shot. Shorter charges scale down to `1.0`.
+    pub max_damage_multiplier: f32,
+}
+
+/// Seconds from a weapon file or upgrade as a [`Duration`].
+/// Negative and NaN values would panic, so they count as zero,
+/// and values too large for a [`Duration`] as [`Duration::MAX`].
+fn duration_from_secs(secs: f32) -> Duration {
+    Duration::try_from_secs_f32(secs.max(0.0)).unwrap_or(Duration::MAX)
+}
+
+impl Weapon {
+    pub fn fire_interval(&self) -> Duration {
+        duration_from_secs(self.fire_interval_secs)
+    }
+
+    pub fn bullet_time_to_live(&self) -> Duration {
+        duration_from_secs(self.bullet_time_to_live_secs)
+    }
+
+    /// The directions to fire a shot's bullets in, fanned out around `aim`.
+    pub fn shot_directions(&self, aim: Dir2) -> impl Iterator<Item = Dir2> {
+        let count = self.projectiles.max(1);
+        let spread = self.spread_degrees.to_radians();
+        let (first, step) = if count > 1 {
+            (-spread / 2.0, spread / (count - 1) as f32)
+        } else {
+            (0.0, 0.0)
+        };
+        (0..count).map(move |index| Rot2::radians(first + step * index as f32) * aim)
+    }
+}
+
+/// The current stats of every weapon.
+/// Starts from the weapon definitions each run, and upgrades from the shop change it.
+#[derive(Resource, Reflect, Default, Clone, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub struct WeaponStats(pub HashMap<WeaponKey, Weapon>);
+
+fn reset_weapon_stats(
+    weapon_handles: Res<HandleMap<WeaponKey>>,
+    weapons: Res<Assets<Weapon>>,
+    mut weapon_stats: ResMut<WeaponStats>,
+) {
+    weapon_stats.0 = weapon_handles
+        .iter()
+        .filter_map(|(&key, handle)| Some((key, weapons.get(handle)?.clone())))
+        .collect();
+}
@@ -1,28 +1,26 @@
+mod args;
 #[cfg(feature = "dev")]
 mod dev_tools;
-mod game;
+pub mod game;
 mod screen;
+mod settings;
+mod storage;
+#[cfg(feature = "test-support")]
+pub mod test_support;
 mod ui;
 
-use bevy::{
-    asset::AssetMetaCheck,
-    audio::{AudioPlugin, Volume},
-    prelude::*,
-};
+use bevy::{asset::AssetMetaCheck, prelude::*};
 use bevy_rapier2d::{
     plugin::{NoUserData, RapierPhysicsPlugin},
     render::RapierDebugRenderPlugin,
 };
+use game::camera::{CameraFollow, CameraShake};
 
 pub struct AppPlugin;
 
 impl Plugin for AppPlugin {
     fn build(&self, app: &mut App) {
-        // Order new `AppStep` variants by adding them here:
-        app.configure_sets(
-            Update,
-            (AppSet::TickTimers, AppSet::RecordInput, AppSet::Update).chain(),
-        );
+        configure_app_sets(app);
 
         // Spawn the main camera.
         app.add_systems(Startup, spawn_camera);
@@ -47,18 +45,19 @@ impl Plugin for AppPlugin {
                     }
                     .into(),
                     ..default()
-                })
-                .set(AudioPlugin {
-                    global_volume: GlobalVolume {
-                        volume: Volume::new(0.3),
-                    },
-                    ..default()
                 }),
-            RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0),
+            // Step physics along with the rest of the simulation in `FixedUpdate`.
+            RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0).in_fixed_schedule(),
         ));
 
         // Add other plugins.
-        app.add_plugins((game::plugin, screen::plugin, ui::plugin));
+        app.add_plugins((
+            storage::plugin,
+            game::plugin,
+            screen::plugin,
+            ui::plugin,
+            settings::plugin,
+        ));
 
         // Enable dev tools for dev builds.
         #[cfg(feature = "dev")]
@@ -66,9 +65,8 @@ impl Plugin for AppPlugin {
     }
 }
 
-/// High-level groupings of systems for the app in the `Update` schedule.
-/// When adding a new variant, make sure to order it in the `configure_sets`
-/// call above.
+/// High-level groupings of systems for the app in the `Update` and `FixedUpdate` schedules.
+/// When adding a new variant, make sure to order it in [`configure_app_sets`].
 #[derive(SystemSet, Debug, Clone, Copy, Eq, PartialEq, Hash)]
 enum AppSet {
     /// Tick timers.
@@ -79,6 +77,18 @@ enum AppSet {
     Update,
 }
 
+fn configure_app_sets(app: &mut App) {
+    // Order new `AppStep` variants by adding them here:
+    app.configure_sets(
+        Update,
+        (AppSet::TickTimers, AppSet::RecordInput, AppSet::Update).chain(),
+    );
+    app.configure_sets(
+        FixedUpdate,
+        (AppSet::TickTimers, AppSet::RecordInput, AppSet::Update).chain(),
+    );
+}
+
 fn spawn_camera(mut commands: Commands) {
     commands.spawn((
         Name::new("Camera"),
@@ -91,6 +101,8 @@ fn spawn_camera(mut commands: Commands) {
             },
             ..default()
         },
+        CameraFollow::default(),
+        CameraShake::default(),
         // Render all UI to this camera.
         // Not strictly necessary since we only use one camera,
         // but if we don't use this component, our UI will disappear as soon
@@ -0,0 +1,59 @@
+//! The screen shown after the player dies.
+
+use bevy::prelude::*;
+
+use super::Screen;
+use crate::{
+    game::{rng::GameRng, stats::RunStats},
+    ui::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::GameOver), enter_game_over);
+
+    app.register_type::<GameOverAction>();
+    app.add_systems(
+        Update,
+        handle_game_over_action.run_if(in_state(Screen::GameOver)),
+    );
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum GameOverAction {
+    Retry,
+    Title,
+}
+
+fn enter_game_over(mut commands: Commands, stats: Res<RunStats>, rng: Res<GameRng>) {
+    commands
+        .ui_root()
+        .insert(StateScoped(Screen::GameOver))
+        .with_children(|children| {
+            children.header("Game Over");
+            children.label(format!("Round: {}", stats.round));
+            children.label(format!("Day: {}", stats.day));
+            children.label(format!("Time: {}s", stats.time.as_secs()));
+            children.label(format!("Kills: {}", stats.kills));
+            children.label(format!("Harvests: {}", stats.harvests));
+            children.label(format!("Coins: {}", stats.coins));
+            children.label(format!("Seed: {}", rng.seed()));
+
+            children.button("Retry").insert(GameOverAction::Retry);
+            children.button("Title").insert(GameOverAction::Title);
+        });
+}
+
+fn handle_game_over_action(
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut button_query: InteractionQuery<&GameOverAction>,
+) {
+    for (interaction, action) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            match action {
+                GameOverAction::Retry => next_screen.set(Screen::Playing),
+                GameOverAction::Title => next_screen.set(Screen::Title),
+            }
+        }
+    }
+}
@@ -5,7 +5,9 @@ use bevy::prelude::*;
 
 use super::Screen;
 use crate::{
-    game::assets::{HandleMap, ImageKey, SfxKey, SoundtrackKey},
+    game::assets::{
+        CatalogKey, HandleMap, ImageKey, LevelKey, PlantKey, SfxKey, SoundtrackKey, WeaponKey,
+    },
     ui::prelude::*,
 };
 
@@ -31,10 +33,18 @@ fn all_assets_loaded(
     image_handles: Res<HandleMap<ImageKey>>,
     sfx_handles: Res<HandleMap<SfxKey>>,
     soundtrack_handles: Res<HandleMap<SoundtrackKey>>,
+    plant_handles: Res<HandleMap<PlantKey>>,
+    catalog_handles: Res<HandleMap<CatalogKey>>,
+    weapon_handles: Res<HandleMap<WeaponKey>>,
+    level_handles: Res<HandleMap<LevelKey>>,
 ) -> bool {
     image_handles.all_loaded(&asset_server)
         && sfx_handles.all_loaded(&asset_server)
         && soundtrack_handles.all_loaded(&asset_server)
+        && plant_handles.all_loaded(&asset_server)
+        && catalog_handles.all_loaded(&asset_server)
+        && weapon_handles.all_loaded(&asset_server)
+        && level_handles.all_loaded(&asset_server)
 }
 
 fn continue_to_title(mut next_screen: ResMut<NextState<Screen>>) {
@@ -1,8 +1,11 @@
 //! The game's main screen states and transitions between them.
 
 mod credits;
+mod game_over;
 mod loading;
+mod pause;
 mod playing;
+mod settings;
 mod splash;
 mod title;
 
@@ -17,7 +20,10 @@ pub(super) fn plugin(app: &mut App) {
         loading::plugin,
         title::plugin,
         credits::plugin,
+        settings::plugin,
         playing::plugin,
+        pause::plugin,
+        game_over::plugin,
     ));
 }
 
@@ -29,5 +35,10 @@ pub enum Screen {
     Loading,
     Title,
     Credits,
+    Settings,
     Playing,
+    /// Passes straight back to [`Screen::Playing`] to start a new run,
+    /// since entering the state we're already in doesn't run `OnEnter`.
+    Restart,
+    GameOver,
 }
@@ -0,0 +1,141 @@
+//! The pause menu, shown over the game while it's paused.
+
+use bevy::{prelude::*, window::WindowFocused};
+
+use super::Screen;
+use crate::{
+    game::{
+        input::{action_just_pressed, Action, ActionState, ActionSystem},
+        replay::recording_or_replaying,
+        rounds::RoundState,
+        save::{SaveFile, SaveRun},
+    },
+    storage::Storage,
+    ui::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_sub_state::<IsPaused>();
+    app.enable_state_scoped_entities::<IsPaused>();
+
+    app.register_type::<PauseAction>();
+    app.add_systems(OnEnter(IsPaused::Paused), (pause_time, enter_pause_menu));
+    app.add_systems(OnEnter(IsPaused::Running), unpause_time);
+    app.add_systems(OnExit(Screen::Playing), unpause_time);
+    app.add_systems(
+        PreUpdate,
+        block_gameplay_actions
+            .after(ActionSystem)
+            .run_if(in_state(IsPaused::Paused).or_else(in_state(IsPaused::Settings))),
+    );
+    app.add_systems(
+        Update,
+        (
+            toggle_pause
+                .run_if(in_state(RoundState::Active).and_then(action_just_pressed(Action::Pause))),
+            // Focus isn't recorded, so it mustn't change how a replay plays out.
+            pause_on_focus_lost.run_if(
+                in_state(RoundState::Active)
+                    .and_then(in_state(IsPaused::Running))
+                    .and_then(not(recording_or_replaying)),
+            ),
+            handle_pause_action.run_if(in_state(IsPaused::Paused)),
+        ),
+    );
+}
+
+/// Whether the game is running or paused. Pausing stops [`Time<Virtual>`],
+/// which freezes everything that moves or grows.
+#[derive(SubStates, Debug, Hash, PartialEq, Eq, Clone, Default)]
+#[source(Screen = Screen::Playing)]
+pub enum IsPaused {
+    #[default]
+    Running,
+    Paused,
+    /// The settings menu, opened from the pause menu.
+    Settings,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum PauseAction {
+    Resume,
+    Settings,
+    Restart,
+    Quit,
+}
+
+fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn unpause_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+fn enter_pause_menu(mut commands: Commands) {
+    commands
+        .ui_root()
+        .insert((
+            Name::new("Pause Menu"),
+            BackgroundColor(ui_palette::OVERLAY_BACKGROUND),
+            StateScoped(IsPaused::Paused),
+        ))
+        .with_children(|children| {
+            children.header("Paused");
+            children.button("Resume").insert(PauseAction::Resume);
+            children.button("Settings").insert(PauseAction::Settings);
+            children.button("Restart").insert(PauseAction::Restart);
+            children.button("Quit to Title").insert(PauseAction::Quit);
+        });
+}
+
+/// Keep the player from acting while a menu is open, except to close it again.
+fn block_gameplay_actions(mut actions: ResMut<ActionState>) {
+    actions.keep_only(Action::Pause);
+}
+
+fn toggle_pause(state: Res<State<IsPaused>>, mut next_state: ResMut<NextState<IsPaused>>) {
+    match state.get() {
+        IsPaused::Running => next_state.set(IsPaused::Paused),
+        IsPaused::Paused => next_state.set(IsPaused::Running),
+        // The settings menu is closed with its back button,
+        // so the pause key can still be rebound there.
+        IsPaused::Settings => {}
+    }
+}
+
+fn pause_on_focus_lost(
+    mut events: EventReader<WindowFocused>,
+    mut next_state: ResMut<NextState<IsPaused>>,
+) {
+    if events.read().any(|event| !event.focused) {
+        next_state.set(IsPaused::Paused);
+    }
+}
+
+fn handle_pause_action(
+    mut next_state: ResMut<NextState<IsPaused>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut button_query: InteractionQuery<&PauseAction>,
+    storage: Res<Storage>,
+    mut commands: Commands,
+) {
+    for (interaction, action) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            match action {
+                PauseAction::Resume => next_state.set(IsPaused::Running),
+                PauseAction::Settings => next_state.set(IsPaused::Settings),
+                PauseAction::Restart => {
+                    // The abandoned run can't be continued anymore.
+                    SaveFile::delete(&storage);
+                    next_screen.set(Screen::Restart);
+                }
+                PauseAction::Quit => {
+                    commands.trigger(SaveRun);
+                    next_screen.set(Screen::Title);
+                }
+            }
+        }
+    }
+}
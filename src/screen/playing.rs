@@ -1,25 +1,27 @@
 //! The screen state for the main game loop.
 
-use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+use bevy::prelude::*;
 
 use super::Screen;
 use crate::game::{
-    assets::SoundtrackKey, audio::soundtrack::PlaySoundtrack, spawn::level::SpawnLevel,
+    assets::SoundtrackKey,
+    audio::soundtrack::PlaySoundtrack,
+    health::Died,
+    levels::SelectedLevel,
+    spawn::{level::SpawnLevel, player::Player},
 };
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::Playing), enter_playing);
     app.add_systems(OnExit(Screen::Playing), exit_playing);
-
-    app.add_systems(
-        Update,
-        return_to_title_screen
-            .run_if(in_state(Screen::Playing).and_then(input_just_pressed(KeyCode::Escape))),
-    );
+    app.add_systems(OnEnter(Screen::Restart), restart);
+    app.observe(game_over);
 }
 
-fn enter_playing(mut commands: Commands) {
-    commands.trigger(SpawnLevel);
+fn enter_playing(selected_level: Res<SelectedLevel>, mut commands: Commands) {
+    commands.trigger(SpawnLevel {
+        level: selected_level.0,
+    });
     commands.trigger(PlaySoundtrack::Key(SoundtrackKey::Gameplay));
 }
 
@@ -28,6 +30,16 @@ fn exit_playing(mut commands: Commands) {
     commands.trigger(PlaySoundtrack::Disable);
 }
 
-fn return_to_title_screen(mut next_screen: ResMut<NextState<Screen>>) {
-    next_screen.set(Screen::Title);
+fn restart(mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Playing);
+}
+
+fn game_over(
+    trigger: Trigger<Died>,
+    players: Query<(), With<Player>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    if players.contains(trigger.entity()) {
+        next_screen.set(Screen::GameOver);
+    }
 }
@@ -0,0 +1,407 @@
+//! A settings screen that can be accessed from the title screen and the pause menu.
+
+use bevy::{
+    ecs::system::EntityCommands,
+    prelude::*,
+    ui::{RelativeCursorPosition, Val::*},
+};
+
+use super::{pause::IsPaused, Screen};
+use crate::{
+    game::{
+        bullets::AimMode,
+        input::{Action, KeySlot, MoveDirection},
+    },
+    settings::Settings,
+    storage::Storage,
+    ui::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Settings), enter_settings);
+    app.add_systems(OnExit(Screen::Settings), exit_settings);
+    app.add_systems(OnEnter(IsPaused::Settings), enter_pause_settings);
+    app.add_systems(OnExit(IsPaused::Settings), exit_settings);
+
+    app.register_type::<(SettingsAction, VolumeSlider, SliderFill, Rebinding)>();
+    app.init_resource::<Rebinding>();
+    app.add_systems(
+        Update,
+        (
+            handle_settings_action,
+            drag_volume_sliders,
+            capture_rebinding,
+            update_slider_fills,
+            update_settings_text,
+        )
+            .chain()
+            .run_if(in_state(Screen::Settings).or_else(in_state(IsPaused::Settings))),
+    );
+}
+
+/// The keys that can be rebound, in the order they are listed.
+const KEY_SLOTS: [(&str, KeySlot); 17] = [
+    ("Up", KeySlot::Direction(Action::Move, MoveDirection::Up)),
+    (
+        "Down",
+        KeySlot::Direction(Action::Move, MoveDirection::Down),
+    ),
+    (
+        "Left",
+        KeySlot::Direction(Action::Move, MoveDirection::Left),
+    ),
+    (
+        "Right",
+        KeySlot::Direction(Action::Move, MoveDirection::Right),
+    ),
+    ("Dash", KeySlot::Action(Action::Dash)),
+    ("Planter", KeySlot::Action(Action::SelectTool(0))),
+    ("Water", KeySlot::Action(Action::SelectTool(1))),
+    ("Pistol", KeySlot::Action(Action::SelectTool(2))),
+    ("Harvester", KeySlot::Action(Action::SelectTool(3))),
+    ("Shotgun", KeySlot::Action(Action::SelectTool(4))),
+    ("Lance", KeySlot::Action(Action::SelectTool(5))),
+    ("Cannon", KeySlot::Action(Action::SelectTool(6))),
+    ("Next Tool", KeySlot::Action(Action::NextTool)),
+    ("Next Seed", KeySlot::Action(Action::NextSeed)),
+    ("Use", KeySlot::Action(Action::Use)),
+    ("Shop", KeySlot::Action(Action::Shop)),
+    ("Pause", KeySlot::Action(Action::Pause)),
+];
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum SettingsAction {
+    ToggleFullscreen,
+    CycleAimMode,
+    ToggleHealthBars,
+    Rebind(KeySlot),
+    Back,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+enum Volume {
+    Master,
+    Music,
+    Sfx,
+}
+
+impl Volume {
+    fn get(self, settings: &Settings) -> f32 {
+        match self {
+            Volume::Master => settings.master_volume,
+            Volume::Music => settings.music_volume,
+            Volume::Sfx => settings.sfx_volume,
+        }
+    }
+
+    fn get_mut(self, settings: &mut Settings) -> &mut f32 {
+        match self {
+            Volume::Master => &mut settings.master_volume,
+            Volume::Music => &mut settings.music_volume,
+            Volume::Sfx => &mut settings.sfx_volume,
+        }
+    }
+}
+
+/// Click or drag anywhere on the slider to set the volume.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct VolumeSlider(Volume);
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct SliderFill(Volume);
+
+/// The key slot waiting for the player to press a new key.
+#[derive(Resource, Reflect, Default)]
+#[reflect(Resource)]
+struct Rebinding(Option<KeySlot>);
+
+fn enter_settings(mut commands: Commands, mut rebinding: ResMut<Rebinding>) {
+    rebinding.0 = None;
+    spawn_settings_menu(&mut commands).insert(StateScoped(Screen::Settings));
+}
+
+fn enter_pause_settings(mut commands: Commands, mut rebinding: ResMut<Rebinding>) {
+    rebinding.0 = None;
+    spawn_settings_menu(&mut commands).insert((
+        BackgroundColor(ui_palette::OVERLAY_BACKGROUND),
+        StateScoped(IsPaused::Settings),
+    ));
+}
+
+fn spawn_settings_menu<'a>(commands: &'a mut Commands) -> EntityCommands<'a> {
+    let mut root = commands.ui_root();
+    root.insert(Name::new("Settings Menu"))
+        .with_children(|children| {
+            children.header("Settings");
+            children
+                .spawn((
+                    Name::new("Columns"),
+                    NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Row,
+                            column_gap: Px(40.0),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                ))
+                .with_children(|columns| {
+                    columns
+                        .spawn(settings_column("Audio and Display"))
+                        .with_children(|column| {
+                            for (name, volume) in [
+                                ("Master Volume", Volume::Master),
+                                ("Music Volume", Volume::Music),
+                                ("Sfx Volume", Volume::Sfx),
+                            ] {
+                                column.label(name);
+                                spawn_slider(column, volume);
+                            }
+                            column.button("").insert(SettingsAction::ToggleFullscreen);
+                            column.button("").insert(SettingsAction::CycleAimMode);
+                            column.button("").insert(SettingsAction::ToggleHealthBars);
+                        });
+                    columns
+                        .spawn(settings_column("Keys"))
+                        .with_children(|column| {
+                            for (name, slot) in KEY_SLOTS {
+                                spawn_key_row(column, name, slot);
+                            }
+                        });
+                });
+            children.button("Back").insert(SettingsAction::Back);
+        });
+    root
+}
+
+fn settings_column(name: &str) -> impl Bundle {
+    (
+        Name::new(name.to_string()),
+        NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Px(6.0),
+                ..default()
+            },
+            ..default()
+        },
+    )
+}
+
+fn spawn_slider(parent: &mut ChildBuilder, volume: Volume) {
+    parent
+        .spawn((
+            Name::new("Volume Slider"),
+            ButtonBundle {
+                style: Style {
+                    width: Px(300.0),
+                    height: Px(30.0),
+                    ..default()
+                },
+                background_color: BackgroundColor(ui_palette::BUTTON_DISABLED_BACKGROUND),
+                ..default()
+            },
+            RelativeCursorPosition::default(),
+            VolumeSlider(volume),
+        ))
+        .with_children(|slider| {
+            slider.spawn((
+                Name::new("Slider Fill"),
+                NodeBundle {
+                    style: Style {
+                        height: Percent(100.0),
+                        ..default()
+                    },
+                    background_color: BackgroundColor(ui_palette::NODE_BACKGROUND),
+                    ..default()
+                },
+                SliderFill(volume),
+            ));
+        });
+}
+
+fn spawn_key_row(parent: &mut ChildBuilder, name: &str, slot: KeySlot) {
+    parent
+        .spawn((
+            Name::new("Key Row"),
+            NodeBundle {
+                style: Style {
+                    width: Px(360.0),
+                    justify_content: JustifyContent::SpaceBetween,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(|row| {
+            row.spawn(TextBundle::from_section(
+                name.to_string(),
+                TextStyle {
+                    font_size: 24.0,
+                    color: ui_palette::LABEL_TEXT,
+                    ..default()
+                },
+            ));
+            row.spawn((
+                Name::new("Rebind Button"),
+                ButtonBundle {
+                    style: Style {
+                        width: Px(160.0),
+                        height: Px(30.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: BackgroundColor(ui_palette::NODE_BACKGROUND),
+                    ..default()
+                },
+                InteractionPalette {
+                    none: ui_palette::NODE_BACKGROUND,
+                    hovered: ui_palette::BUTTON_HOVERED_BACKGROUND,
+                    pressed: ui_palette::BUTTON_PRESSED_BACKGROUND,
+                },
+                SettingsAction::Rebind(slot),
+            ))
+            .with_children(|button| {
+                button.spawn(TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 24.0,
+                        color: ui_palette::BUTTON_TEXT,
+                        ..default()
+                    },
+                ));
+            });
+        });
+}
+
+fn exit_settings(settings: Res<Settings>, storage: Res<Storage>) {
+    settings.save(&storage);
+}
+
+fn handle_settings_action(
+    paused: Option<Res<State<IsPaused>>>,
+    mut next_paused: ResMut<NextState<IsPaused>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut button_query: InteractionQuery<&SettingsAction>,
+    mut settings: ResMut<Settings>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    for (interaction, action) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            match action {
+                SettingsAction::ToggleFullscreen => settings.fullscreen = !settings.fullscreen,
+                SettingsAction::CycleAimMode => settings.aim_mode = settings.aim_mode.next(),
+                SettingsAction::ToggleHealthBars => settings.health_bars = !settings.health_bars,
+                SettingsAction::Rebind(slot) => rebinding.0 = Some(*slot),
+                // Go back to wherever the settings were opened from.
+                SettingsAction::Back if paused.is_some() => next_paused.set(IsPaused::Paused),
+                SettingsAction::Back => next_screen.set(Screen::Title),
+            }
+        }
+    }
+}
+
+fn drag_volume_sliders(
+    sliders: Query<(&VolumeSlider, &Interaction, &RelativeCursorPosition)>,
+    mut settings: ResMut<Settings>,
+) {
+    for (slider, interaction, cursor) in &sliders {
+        if !matches!(interaction, Interaction::Pressed) {
+            continue;
+        }
+        let Some(position) = cursor.normalized else {
+            continue;
+        };
+        let value = position.x.clamp(0.0, 1.0);
+        let volume = slider.0.get_mut(settings.bypass_change_detection());
+        if *volume != value {
+            *volume = value;
+            settings.set_changed();
+        }
+    }
+}
+
+fn capture_rebinding(
+    input: Res<ButtonInput<KeyCode>>,
+    mut rebinding: ResMut<Rebinding>,
+    mut settings: ResMut<Settings>,
+) {
+    let Some(slot) = rebinding.0 else {
+        return;
+    };
+    let Some(&key_code) = input.get_just_pressed().next() else {
+        return;
+    };
+
+    rebinding.0 = None;
+    // Escape cancels rebinding, unless Escape is what's being rebound.
+    if key_code == KeyCode::Escape && slot != KeySlot::Action(Action::Pause) {
+        return;
+    }
+    settings.bindings.rebind_key(slot, key_code);
+}
+
+fn update_slider_fills(settings: Res<Settings>, mut fills: Query<(&SliderFill, &mut Style)>) {
+    for (fill, mut style) in &mut fills {
+        style.width = Percent(fill.0.get(&settings) * 100.0);
+    }
+}
+
+fn update_settings_text(
+    settings: Res<Settings>,
+    rebinding: Res<Rebinding>,
+    buttons: Query<(&SettingsAction, &Children)>,
+    mut texts: Query<&mut Text>,
+) {
+    for (action, children) in &buttons {
+        let value = match action {
+            SettingsAction::ToggleFullscreen => {
+                if settings.fullscreen {
+                    "Fullscreen".to_string()
+                } else {
+                    "Windowed".to_string()
+                }
+            }
+            SettingsAction::CycleAimMode => match settings.aim_mode {
+                AimMode::AutoNearest => "Auto Aim".to_string(),
+                AimMode::Cursor => "Cursor Aim".to_string(),
+                AimMode::MovementDirection => "Movement Aim".to_string(),
+            },
+            SettingsAction::ToggleHealthBars => {
+                if settings.health_bars {
+                    "Health Bars On".to_string()
+                } else {
+                    "Health Bars Off".to_string()
+                }
+            }
+            SettingsAction::Rebind(slot) if rebinding.0 == Some(*slot) => "...".to_string(),
+            SettingsAction::Rebind(slot) => settings
+                .bindings
+                .key(*slot)
+                .map_or("None".to_string(), key_name),
+            SettingsAction::Back => continue,
+        };
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                if text.sections[0].value != value {
+                    text.sections[0].value.clone_from(&value);
+                }
+            }
+        }
+    }
+}
+
+/// A short, readable name for a key.
+fn key_name(key_code: KeyCode) -> String {
+    let name = format!("{key_code:?}");
+    name.strip_prefix("Key")
+        .or_else(|| name.strip_prefix("Digit"))
+        .unwrap_or(&name)
+        .to_string()
+}
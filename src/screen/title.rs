@@ -3,7 +3,15 @@
 use bevy::prelude::*;
 
 use super::Screen;
-use crate::ui::prelude::*;
+use crate::{
+    game::{
+        assets::{HandleMap, LevelKey},
+        levels::{LevelLayout, SelectedLevel},
+        save::{PendingLoad, SaveFile},
+    },
+    storage::Storage,
+    ui::prelude::*,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::Title), enter_title);
@@ -15,19 +23,41 @@ pub(super) fn plugin(app: &mut App) {
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
 #[reflect(Component)]
 enum TitleAction {
-    Play,
+    /// Pick up the saved run where it left off.
+    Continue,
+    Play(LevelKey),
+    Settings,
     Credits,
     /// Exit doesn't work well with embedded applications.
     #[cfg(not(target_family = "wasm"))]
     Exit,
 }
 
-fn enter_title(mut commands: Commands) {
+fn enter_title(
+    level_handles: Res<HandleMap<LevelKey>>,
+    layouts: Res<Assets<LevelLayout>>,
+    storage: Res<Storage>,
+    mut commands: Commands,
+) {
     commands
         .ui_root()
         .insert(StateScoped(Screen::Title))
         .with_children(|children| {
-            children.button("Play").insert(TitleAction::Play);
+            if SaveFile::load(&storage).is_some() {
+                children.button("Continue").insert(TitleAction::Continue);
+            }
+            for level in LevelKey::ALL {
+                let Some(layout) = level_handles
+                    .get(&level)
+                    .and_then(|handle| layouts.get(handle))
+                else {
+                    continue;
+                };
+                children
+                    .button(format!("Play {}", layout.name))
+                    .insert(TitleAction::Play(level));
+            }
+            children.button("Settings").insert(TitleAction::Settings);
             children.button("Credits").insert(TitleAction::Credits);
 
             #[cfg(not(target_family = "wasm"))]
@@ -38,12 +68,25 @@ fn enter_title(mut commands: Commands) {
 fn handle_title_action(
     mut next_screen: ResMut<NextState<Screen>>,
     mut button_query: InteractionQuery<&TitleAction>,
+    storage: Res<Storage>,
+    mut commands: Commands,
     #[cfg(not(target_family = "wasm"))] mut app_exit: EventWriter<AppExit>,
 ) {
     for (interaction, action) in &mut button_query {
         if matches!(interaction, Interaction::Pressed) {
             match action {
-                TitleAction::Play => next_screen.set(Screen::Playing),
+                TitleAction::Continue => {
+                    if let Some(save) = SaveFile::load(&storage) {
+                        commands.insert_resource(SelectedLevel(save.level()));
+                        commands.insert_resource(PendingLoad(save));
+                    }
+                    next_screen.set(Screen::Playing);
+                }
+                TitleAction::Play(level) => {
+                    commands.insert_resource(SelectedLevel(*level));
+                    next_screen.set(Screen::Playing);
+                }
+                TitleAction::Settings => next_screen.set(Screen::Settings),
                 TitleAction::Credits => next_screen.set(Screen::Credits),
 
                 #[cfg(not(target_family = "wasm"))]
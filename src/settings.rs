@@ -0,0 +1,114 @@
+//! Player settings that are loaded at startup and saved with [`storage`](crate::storage).
+
+use bevy::{
+    prelude::*,
+    window::{PrimaryWindow, WindowMode},
+};
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game::{bullets::AimMode, input::ActionMap},
+    storage::Storage,
+};
+
+const SETTINGS_KEY: &str = "settings";
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Settings>();
+    // Loaded right away, from the storage that's added before this plugin.
+    let settings = Settings::load(app.world().resource::<Storage>());
+    app.insert_resource(settings);
+    app.add_systems(Update, apply_settings.run_if(resource_changed::<Settings>));
+}
+
+#[derive(Resource, Reflect, Clone, Debug, Serialize, Deserialize)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct Settings {
+    /// Volume of all audio, from `0.0` to `1.0`.
+    pub master_volume: f32,
+    /// Volume of the soundtrack, relative to the master volume.
+    pub music_volume: f32,
+    /// Volume of sound effects, relative to the master volume.
+    pub sfx_volume: f32,
+    pub fullscreen: bool,
+    /// How weapons pick where to fire.
+    pub aim_mode: AimMode,
+    /// Whether damaged entities show a health bar.
+    pub health_bars: bool,
+    pub bindings: ActionMap,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            master_volume: 0.3,
+            music_volume: 1.0,
+            sfx_volume: 1.0,
+            fullscreen: false,
+            aim_mode: AimMode::default(),
+            health_bars: true,
+            bindings: ActionMap::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// Load the saved settings, falling back to the defaults.
+    pub fn load(storage: &Storage) -> Self {
+        let mut settings: Self = storage
+            .read(SETTINGS_KEY)
+            .and_then(|contents| {
+                ron::from_str(&contents)
+                    .map_err(|error| warn!("Could not read settings: {error}"))
+                    .ok()
+            })
+            .unwrap_or_default();
+        // Settings saved by older versions don't know about newer actions.
+        settings.bindings.add_missing_defaults();
+        settings
+    }
+
+    pub fn save(&self, storage: &Storage) {
+        let result = ron::ser::to_string_pretty(self, PrettyConfig::default())
+            .map_err(|error| error.to_string())
+            .and_then(|contents| {
+                storage
+                    .write(SETTINGS_KEY, &contents)
+                    .map_err(|error| error.to_string())
+            });
+        if let Err(error) = result {
+            warn!("Could not save settings: {error}");
+        }
+    }
+
+    pub fn music_volume(&self) -> f32 {
+        self.master_volume * self.music_volume
+    }
+
+    pub fn sfx_volume(&self) -> f32 {
+        self.master_volume * self.sfx_volume
+    }
+}
+
+fn apply_settings(
+    settings: Res<Settings>,
+    mut action_map: ResMut<ActionMap>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if *action_map != settings.bindings {
+        *action_map = settings.bindings.clone();
+    }
+
+    let mode = if settings.fullscreen {
+        WindowMode::BorderlessFullscreen
+    } else {
+        WindowMode::Windowed
+    };
+    for mut window in &mut windows {
+        if window.mode != mode {
+            window.mode = mode;
+        }
+    }
+}
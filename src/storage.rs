@@ -0,0 +1,131 @@
+//! Read and write small text files that persist between runs.
+//! Native builds use the platform's config directory and web builds use local storage.
+
+use bevy::prelude::*;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error("could not access storage: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("storage is not available")]
+    Unavailable,
+}
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Storage>();
+}
+
+/// Where [`Storage`] keeps its files.
+pub trait StorageBackend: Send + Sync + 'static {
+    fn read(&self, key: &str) -> Option<String>;
+    fn write(&self, key: &str, contents: &str) -> Result<(), StorageError>;
+    fn remove(&self, key: &str) -> Result<(), StorageError>;
+}
+
+/// The storage the game reads and writes, which is the platform's by default.
+/// Insert one with another [`StorageBackend`] before adding the plugins to keep files elsewhere.
+#[derive(Resource)]
+pub struct Storage(Box<dyn StorageBackend>);
+
+impl Default for Storage {
+    fn default() -> Self {
+        Self::new(platform::PlatformStorage)
+    }
+}
+
+impl Storage {
+    pub fn new(backend: impl StorageBackend) -> Self {
+        Self(Box::new(backend))
+    }
+
+    pub fn read(&self, key: &str) -> Option<String> {
+        self.0.read(key)
+    }
+
+    pub fn write(&self, key: &str, contents: &str) -> Result<(), StorageError> {
+        self.0.write(key, contents)
+    }
+
+    pub fn remove(&self, key: &str) -> Result<(), StorageError> {
+        self.0.remove(key)
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+mod platform {
+    use std::{fs, path::PathBuf};
+
+    use directories::ProjectDirs;
+
+    use super::{StorageBackend, StorageError};
+
+    /// Files in the platform's config directory.
+    pub struct PlatformStorage;
+
+    fn path(key: &str) -> Option<PathBuf> {
+        ProjectDirs::from("", "", "bevy-jam-5")
+            .map(|dirs| dirs.config_dir().join(format!("{key}.ron")))
+    }
+
+    impl StorageBackend for PlatformStorage {
+        fn read(&self, key: &str) -> Option<String> {
+            fs::read_to_string(path(key)?).ok()
+        }
+
+        fn write(&self, key: &str, contents: &str) -> Result<(), StorageError> {
+            let path = path(key).ok_or(StorageError::Unavailable)?;
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, contents)?;
+            Ok(())
+        }
+
+        fn remove(&self, key: &str) -> Result<(), StorageError> {
+            let path = path(key).ok_or(StorageError::Unavailable)?;
+            match fs::remove_file(path) {
+                Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(error.into()),
+                _ => Ok(()),
+            }
+        }
+    }
+}
+
+#[cfg(target_family = "wasm")]
+mod platform {
+    use web_sys::Storage;
+
+    use super::{StorageBackend, StorageError};
+
+    /// The browser's local storage.
+    pub struct PlatformStorage;
+
+    fn local_storage() -> Option<Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+
+    fn item(key: &str) -> String {
+        format!("bevy-jam-5/{key}")
+    }
+
+    impl StorageBackend for PlatformStorage {
+        fn read(&self, key: &str) -> Option<String> {
+            local_storage()?.get_item(&item(key)).ok()?
+        }
+
+        fn write(&self, key: &str, contents: &str) -> Result<(), StorageError> {
+            local_storage()
+                .ok_or(StorageError::Unavailable)?
+                .set_item(&item(key), contents)
+                .map_err(|_| StorageError::Unavailable)
+        }
+
+        fn remove(&self, key: &str) -> Result<(), StorageError> {
+            local_storage()
+                .ok_or(StorageError::Unavailable)?
+                .remove_item(&item(key))
+                .map_err(|_| StorageError::Unavailable)
+        }
+    }
+}
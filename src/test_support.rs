@@ -0,0 +1,313 @@
+//! A headless version of the game for integration tests.
+//! [`TestApp`] builds the whole game plugin stack under [`MinimalPlugins`] and Rapier,
+//! without a window or rendering, so tests can script a run frame by frame.
+//!
+//! Saves and settings are kept in memory, so tests never touch the player's own files.
+//! Only built with the `test-support` feature, which the integration tests enable.
+
+use std::{
+    collections::HashMap,
+    sync::{Mutex, MutexGuard},
+    time::Duration,
+};
+
+use bevy::{
+    ecs::query::QueryFilter,
+    input::{
+        keyboard::{Key, KeyboardInput, NativeKey},
+        ButtonState, InputPlugin,
+    },
+    prelude::*,
+    state::app::StatesPlugin,
+    time::TimeUpdateStrategy,
+    window::PrimaryWindow,
+};
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    configure_app_sets,
+    game::{
+        self,
+        assets::{
+            CatalogKey, HandleMap, ImageKey, LevelKey, PlantKey, SfxKey, SoundtrackKey, WeaponKey,
+        },
+        levels::{LevelLayout, SelectedLevel},
+        shop::ShopCatalog,
+        spawn::{level::SpawnLevel, player::Player},
+        species::PlantSpecies,
+        weapons::Weapon,
+    },
+    screen::Screen,
+    settings::Settings,
+    spawn_camera,
+    storage::{Storage, StorageBackend, StorageError},
+};
+
+/// The real plant, shop, weapon and level definitions, inserted directly instead of loaded,
+/// so tests don't have to wait for the `AssetServer`.
+const PLANT_FILES: [(PlantKey, &str); 4] = [
+    (
+        PlantKey::Weed,
+        include_str!("../assets/plants/weed.plant.ron"),
+    ),
+    (
+        PlantKey::Flower,
+        include_str!("../assets/plants/flower.plant.ron"),
+    ),
+    (
+        PlantKey::Cactus,
+        include_str!("../assets/plants/cactus.plant.ron"),
+    ),
+    (
+        PlantKey::Tuber,
+        include_str!("../assets/plants/tuber.plant.ron"),
+    ),
+];
+const CATALOG_FILE: &str = include_str!("../assets/shop.catalog.ron");
+const WEAPON_FILES: [(WeaponKey, &str); 4] = [
+    (
+        WeaponKey::Pistol,
+        include_str!("../assets/weapons/pistol.weapon.ron"),
+    ),
+    (
+        WeaponKey::Shotgun,
+        include_str!("../assets/weapons/shotgun.weapon.ron"),
+    ),
+    (
+        WeaponKey::Lance,
+        include_str!("../assets/weapons/lance.weapon.ron"),
+    ),
+    (
+        WeaponKey::Cannon,
+        include_str!("../assets/weapons/cannon.weapon.ron"),
+    ),
+];
+const LEVEL_FILES: [(LevelKey, &str); 2] = [
+    (
+        LevelKey::Field,
+        include_str!("../assets/levels/field.level.ron"),
+    ),
+    (
+        LevelKey::Garden,
+        include_str!("../assets/levels/garden.level.ron"),
+    ),
+];
+
+#[derive(Deref, DerefMut)]
+pub struct TestApp(pub App);
+
+impl Default for TestApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestApp {
+    pub fn new() -> Self {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            AssetPlugin::default(),
+            HierarchyPlugin,
+            TransformPlugin,
+            InputPlugin,
+            RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0).in_fixed_schedule(),
+        ));
+        app.init_asset::<Mesh>();
+        app.init_asset::<ColorMaterial>();
+        app.init_asset::<TextureAtlasLayout>();
+        configure_app_sets(&mut app);
+
+        // Use the default settings instead of the player's.
+        app.insert_resource(Settings::default());
+        app.insert_resource(Storage::new(MemoryStorage::default()));
+        app.init_state::<Screen>();
+        app.enable_state_scoped_entities::<Screen>();
+
+        // Images and audio can't be loaded without rendering and audio plugins,
+        // so they get empty handles before the game tries to load them.
+        app.insert_resource(HandleMap::<ImageKey>::from([(
+            ImageKey::Ducky,
+            Handle::default(),
+        )]));
+        app.insert_resource(HandleMap::<SfxKey>::from(
+            [
+                SfxKey::ButtonHover,
+                SfxKey::ButtonPress,
+                SfxKey::Step1,
+                SfxKey::Step2,
+                SfxKey::Step3,
+                SfxKey::Step4,
+            ]
+            .map(|key| (key, Handle::default())),
+        ));
+        app.insert_resource(HandleMap::<SoundtrackKey>::from(
+            [SoundtrackKey::Credits, SoundtrackKey::Gameplay].map(|key| (key, Handle::default())),
+        ));
+        let plant_handles = PLANT_FILES.map(|(key, _)| (key, stand_in_handle(key as u128)));
+        app.insert_resource(HandleMap::<PlantKey>::from(plant_handles.clone()));
+        let catalog_handle = stand_in_handle(PLANT_FILES.len() as u128);
+        app.insert_resource(HandleMap::<CatalogKey>::from([(
+            CatalogKey::Shop,
+            catalog_handle.clone(),
+        )]));
+        let weapon_handles = WEAPON_FILES.map(|(key, _)| {
+            let index = PLANT_FILES.len() + 1 + key as usize;
+            (key, stand_in_handle(index as u128))
+        });
+        app.insert_resource(HandleMap::<WeaponKey>::from(weapon_handles.clone()));
+        let level_handles = LEVEL_FILES.map(|(key, _)| {
+            let index = PLANT_FILES.len() + 1 + WEAPON_FILES.len() + key as usize;
+            (key, stand_in_handle(index as u128))
+        });
+        app.insert_resource(HandleMap::<LevelKey>::from(level_handles.clone()));
+
+        app.add_plugins(game::plugin);
+
+        for ((_, handle), (key, contents)) in plant_handles.into_iter().zip(PLANT_FILES) {
+            let species: PlantSpecies = ron::from_str(contents)
+                .unwrap_or_else(|error| panic!("Could not parse {key:?}: {error}"));
+            app.world_mut()
+                .resource_mut::<Assets<PlantSpecies>>()
+                .insert(handle.id(), species);
+        }
+        let catalog: ShopCatalog = ron::from_str(CATALOG_FILE)
+            .unwrap_or_else(|error| panic!("Could not parse the shop catalog: {error}"));
+        app.world_mut()
+            .resource_mut::<Assets<ShopCatalog>>()
+            .insert(catalog_handle.id(), catalog);
+        for ((_, handle), (key, contents)) in weapon_handles.into_iter().zip(WEAPON_FILES) {
+            let weapon: Weapon = ron::from_str(contents)
+                .unwrap_or_else(|error| panic!("Could not parse {key:?}: {error}"));
+            app.world_mut()
+                .resource_mut::<Assets<Weapon>>()
+                .insert(handle.id(), weapon);
+        }
+        for ((_, handle), (key, contents)) in level_handles.into_iter().zip(LEVEL_FILES) {
+            let layout: LevelLayout = ron::from_str(contents)
+                .unwrap_or_else(|error| panic!("Could not parse {key:?}: {error}"));
+            app.world_mut()
+                .resource_mut::<Assets<LevelLayout>>()
+                .insert(handle.id(), layout);
+        }
+
+        // Wrapping around the screen needs a window and camera to measure.
+        app.world_mut().spawn((
+            Name::new("Stand-in Window"),
+            Window::default(),
+            PrimaryWindow,
+        ));
+        app.add_systems(Startup, spawn_camera);
+        app.add_systems(OnEnter(Screen::Playing), spawn_level);
+
+        // Every update runs exactly one fixed step.
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Self::timestep()));
+        app.update();
+        Self(app)
+    }
+
+    fn timestep() -> Duration {
+        Time::<Fixed>::default().timestep()
+    }
+
+    /// Start a new run, like pressing Play on the title screen.
+    pub fn start_run(&mut self) {
+        self.world_mut()
+            .resource_mut::<NextState<Screen>>()
+            .set(Screen::Playing);
+        self.update();
+    }
+
+    /// Run until `duration` of game time has passed.
+    pub fn advance(&mut self, duration: Duration) {
+        let steps = duration.as_nanos().div_ceil(Self::timestep().as_nanos());
+        for _ in 0..steps {
+            self.update();
+        }
+    }
+
+    /// Run one update at a time until `done` returns `true`, for at most `timeout` of game time.
+    /// Returns whether `done` returned `true` in time.
+    pub fn advance_until(
+        &mut self,
+        timeout: Duration,
+        mut done: impl FnMut(&mut World) -> bool,
+    ) -> bool {
+        let steps = timeout.as_nanos().div_ceil(Self::timestep().as_nanos());
+        for _ in 0..steps {
+            self.update();
+            if done(self.world_mut()) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Press a key for one update, then release it.
+    pub fn tap(&mut self, key_code: KeyCode) {
+        for state in [ButtonState::Pressed, ButtonState::Released] {
+            self.world_mut().send_event(KeyboardInput {
+                key_code,
+                logical_key: Key::Unidentified(NativeKey::Unidentified),
+                state,
+                window: Entity::PLACEHOLDER,
+            });
+            self.update();
+        }
+    }
+
+    pub fn player(&mut self) -> Entity {
+        let world = self.world_mut();
+        world.query_filtered::<Entity, With<Player>>().single(world)
+    }
+
+    /// How many entities match the filter.
+    pub fn count<F: QueryFilter>(&mut self) -> usize {
+        count::<F>(self.world_mut())
+    }
+}
+
+/// How many entities in the world match the filter.
+pub fn count<F: QueryFilter>(world: &mut World) -> usize {
+    world.query_filtered::<(), F>().iter(world).count()
+}
+
+/// A handle for an asset that is inserted directly instead of loaded.
+fn stand_in_handle<A: Asset>(index: u128) -> Handle<A> {
+    const BASE: u128 = 0x7e57_0000_0000_0000_0000_0000_0000_0000;
+    Handle::weak_from_u128(BASE + index)
+}
+
+fn spawn_level(selected_level: Res<SelectedLevel>, mut commands: Commands) {
+    commands.trigger(SpawnLevel {
+        level: selected_level.0,
+    });
+}
+
+/// Storage that only lives in memory, one per [`TestApp`],
+/// so tests never touch the player's files or each other's.
+#[derive(Default)]
+struct MemoryStorage(Mutex<HashMap<String, String>>);
+
+impl MemoryStorage {
+    fn files(&self) -> MutexGuard<'_, HashMap<String, String>> {
+        self.0.lock().unwrap_or_else(|error| error.into_inner())
+    }
+}
+
+impl StorageBackend for MemoryStorage {
+    fn read(&self, key: &str) -> Option<String> {
+        self.files().get(key).cloned()
+    }
+
+    fn write(&self, key: &str, contents: &str) -> Result<(), StorageError> {
+        self.files().insert(key.to_string(), contents.to_string());
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> Result<(), StorageError> {
+        self.files().remove(key);
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+//! Asset files that need registering in code, and values in them that could break the game.
+
+use std::time::Duration;
+
+use bevy_jam_5::game::{assets::PlantKey, shop::GoodEffect, weapons::Weapon};
+
+#[test]
+fn every_plant_file_has_a_key() {
+    let plant_files = std::fs::read_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/plants"))
+        .unwrap()
+        .filter(|entry| {
+            entry
+                .as_ref()
+                .is_ok_and(|entry| entry.file_name().to_string_lossy().ends_with(".plant.ron"))
+        })
+        .count();
+    assert_eq!(
+        plant_files,
+        PlantKey::ALL.len(),
+        "every file in assets/plants/ needs a PlantKey, see the species module"
+    );
+}
+
+#[test]
+fn non_positive_fire_rates_are_rejected() {
+    assert!(ron::from_str::<GoodEffect>("FireRate(1.25)").is_ok());
+    for multiplier in ["0.0", "-1.0", "inf", "NaN"] {
+        assert!(
+            ron::from_str::<GoodEffect>(&format!("FireRate({multiplier})")).is_err(),
+            "a fire rate multiplier of {multiplier} should be rejected"
+        );
+    }
+}
+
+#[test]
+fn broken_weapon_times_do_not_panic() {
+    let mut weapon: Weapon =
+        ron::from_str(include_str!("../assets/weapons/pistol.weapon.ron")).unwrap();
+    weapon.fire_interval_secs = -1.0;
+    weapon.bullet_time_to_live_secs = f32::NAN;
+    assert_eq!(weapon.fire_interval(), Duration::ZERO);
+    assert_eq!(weapon.bullet_time_to_live(), Duration::ZERO);
+
+    weapon.bullet_time_to_live_secs = f32::INFINITY;
+    assert_eq!(weapon.bullet_time_to_live(), Duration::MAX);
+}
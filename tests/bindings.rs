@@ -0,0 +1,29 @@
+//! Key bindings saved by older versions still get newer actions.
+
+use bevy_jam_5::game::input::{Action, ActionMap};
+
+#[test]
+fn missing_actions_get_their_default_bindings() {
+    let defaults = ActionMap::default();
+    let mut saved = defaults.clone();
+    // Saved before aiming and the extra weapon slots existed, with dashing unbound on purpose.
+    saved.bindings.remove(&Action::Aim);
+    for slot in 4..=6 {
+        saved.bindings.remove(&Action::SelectTool(slot));
+    }
+    saved.bindings.insert(Action::Dash, Vec::new());
+
+    saved.add_missing_defaults();
+
+    assert_eq!(
+        saved.bindings.get(&Action::Aim),
+        defaults.bindings.get(&Action::Aim)
+    );
+    for slot in 4..=6 {
+        assert_eq!(
+            saved.bindings.get(&Action::SelectTool(slot)),
+            defaults.bindings.get(&Action::SelectTool(slot))
+        );
+    }
+    assert_eq!(saved.bindings.get(&Action::Dash), Some(&Vec::new()));
+}
@@ -0,0 +1,100 @@
+//! The simulation runs in fixed timesteps, so the frame rate shouldn't change its results.
+
+use std::time::Duration;
+
+use bevy::{input::InputPlugin, prelude::*, time::TimeUpdateStrategy};
+use bevy_jam_5::game::{
+    bullets::{self, SpawnBullet},
+    health::DamageType,
+    input,
+    interpolation::{self, InterpolateTranslation},
+    movement::{self, MovementController, MovementSettings},
+};
+use bevy_rapier2d::prelude::*;
+
+/// Hold right for a little over two seconds at the given frame rate while a bullet flies,
+/// and return where the simulation left the player and the bullet.
+fn simulate(fps: u32) -> (Vec3, Vec3) {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        HierarchyPlugin,
+        TransformPlugin,
+        InputPlugin,
+        RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0).in_fixed_schedule(),
+    ));
+    app.init_asset::<Mesh>();
+    app.init_asset::<ColorMaterial>();
+    app.add_plugins((
+        input::plugin,
+        interpolation::plugin,
+        movement::plugin,
+        bullets::plugin,
+    ));
+
+    let player = app
+        .world_mut()
+        .spawn((
+            TransformBundle::default(),
+            MovementController::default(),
+            MovementSettings { max_speed: 800.0 },
+            InterpolateTranslation::default(),
+        ))
+        .id();
+
+    // Record the input without letting any time pass,
+    // so that both frame rates start moving on the first fixed step.
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::ZERO));
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .press(KeyCode::KeyD);
+    app.update();
+    app.world_mut().trigger(SpawnBullet {
+        damage: 1.0,
+        damage_type: DamageType::Physical,
+        source: None,
+        position: Vec2::ZERO,
+        direction: Dir2::new(Vec2::new(1.0, 2.0)).unwrap(),
+        speed: 1000.0,
+        time_to_live: Duration::from_secs(10),
+        collision_groups: CollisionGroups::new(Group::GROUP_1, Group::NONE),
+        radius: 10.0,
+        pierce: 0,
+        knockback: 0.0,
+        effects: Vec::new(),
+    });
+
+    // 2.1 seconds is between two fixed steps, so both frame rates run the same number of them.
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(
+        Duration::from_secs(1) / fps,
+    ));
+    for _ in 0..fps * 21 / 10 {
+        app.update();
+    }
+
+    let mut positions = app.world_mut().query::<(Entity, &InterpolateTranslation)>();
+    let mut player_position = None;
+    let mut bullet_position = None;
+    for (entity, interpolation) in positions.iter(app.world()) {
+        if entity == player {
+            player_position = interpolation.simulated();
+        } else {
+            bullet_position = interpolation.simulated();
+        }
+    }
+    (
+        player_position.expect("the player should have moved"),
+        bullet_position.expect("the bullet should still be flying"),
+    )
+}
+
+#[test]
+fn same_positions_at_30_and_144_fps() {
+    let (player_30, bullet_30) = simulate(30);
+    let (player_144, bullet_144) = simulate(144);
+
+    assert!(player_30.x > 0.0, "the player should have moved right");
+    assert_eq!(player_30, player_144);
+    assert_eq!(bullet_30, bullet_144);
+}
@@ -0,0 +1,83 @@
+//! Parsing of level tile grids, and keeping entities out of their solid tiles.
+
+use bevy::prelude::*;
+use bevy_jam_5::game::{
+    assets::LevelKey,
+    levels::{Tile, TileGrid, TileGridError},
+    spawn::level::Level,
+};
+
+fn grid(rows: &[&str]) -> Result<TileGrid, TileGridError> {
+    TileGrid::try_from(rows.iter().map(|row| row.to_string()).collect::<Vec<_>>())
+}
+
+#[test]
+fn tiles_are_centered_with_the_first_row_on_top() {
+    let grid = grid(&["#S.", "~@S"]).unwrap();
+    let tiles: Vec<_> = grid.iter().collect();
+    assert_eq!(tiles[0], (Vec2::new(-1.0, 0.5), Tile::Wall));
+    assert_eq!(tiles[4], (Vec2::new(0.0, -0.5), Tile::Spawn));
+    assert_eq!(grid.size(), Vec2::new(3.0, 2.0));
+}
+
+#[test]
+fn malformed_grids_are_rejected() {
+    assert_eq!(grid(&[]).unwrap_err(), TileGridError::Empty);
+    assert_eq!(
+        grid(&["S@S", "SS"]).unwrap_err(),
+        TileGridError::RaggedRow {
+            row: 1,
+            width: 2,
+            expected: 3
+        }
+    );
+    assert_eq!(
+        grid(&["S@", "Sx"]).unwrap_err(),
+        TileGridError::UnknownTile {
+            tile: 'x',
+            row: 1,
+            column: 1
+        }
+    );
+    assert_eq!(grid(&["SS"]).unwrap_err(), TileGridError::Spawns(0));
+    assert_eq!(grid(&["@@"]).unwrap_err(), TileGridError::Spawns(2));
+}
+
+#[test]
+fn neighbouring_solid_tiles_are_merged() {
+    let grid = grid(&["####", "#S@~", "#SS~"]).unwrap();
+    let solid = grid.merged(Tile::is_solid);
+    assert_eq!(
+        solid,
+        vec![
+            Rect::new(-2.0, 0.5, 2.0, 1.5),
+            Rect::new(-2.0, -1.5, -1.0, 0.5),
+            Rect::new(1.0, -1.5, 2.0, 0.5),
+        ]
+    );
+}
+
+#[test]
+fn entities_are_pushed_out_of_overlapping_solid_areas() {
+    // An L-shaped wall, padded so the two arms overlap in the corner.
+    let level = Level {
+        key: LevelKey::Garden,
+        bounds: Rect::new(-1000.0, -1000.0, 1000.0, 1000.0),
+        camera_scale: 1.0,
+        wrap: false,
+        solid: vec![
+            Rect::new(-100.0, 0.0, 500.0, 200.0),
+            Rect::new(-100.0, -500.0, 100.0, 200.0),
+        ],
+    };
+    assert_eq!(level.push_out_of_solid(Vec2::new(300.0, 300.0)), None);
+    // Leaving either arm the shortest way would end up in the other one.
+    assert_eq!(
+        level.push_out_of_solid(Vec2::new(90.0, 10.0)),
+        Some(Vec2::new(100.0, 0.0))
+    );
+    assert_eq!(
+        level.push_out_of_solid(Vec2::new(300.0, 190.0)),
+        Some(Vec2::new(300.0, 200.0))
+    );
+}
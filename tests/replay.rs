@@ -0,0 +1,174 @@
+//! A recorded run plays back exactly the same way without any input.
+
+use std::time::Duration;
+
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput},
+        ButtonState, InputPlugin,
+    },
+    prelude::*,
+    time::TimeUpdateStrategy,
+};
+use bevy_jam_5::game::{
+    assets::LevelKey,
+    input::{self, Action},
+    interpolation::{self, InterpolateTranslation},
+    movement::{self, Dash, DashSettings, MovementController, MovementSettings},
+    replay::{self, Replay, ReplayPlayback, ReplayRecorder, StartPlayback},
+    rng::{self, point_near, GameRng},
+};
+use bevy_rapier2d::prelude::*;
+
+fn headless_app() -> (App, Entity) {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        HierarchyPlugin,
+        TransformPlugin,
+        InputPlugin,
+        RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0).in_fixed_schedule(),
+    ));
+    app.add_plugins((
+        input::plugin,
+        interpolation::plugin,
+        movement::plugin,
+        rng::plugin,
+        replay::plugin,
+    ));
+    app.add_systems(Update, drop_marker_on_dash);
+
+    let player = app
+        .world_mut()
+        .spawn((
+            TransformBundle::default(),
+            MovementController::default(),
+            MovementSettings { max_speed: 800.0 },
+            DashSettings {
+                intent_window: Duration::from_millis(100),
+                distance: 500.0,
+                time: Duration::from_millis(200),
+            },
+            InterpolateTranslation::default(),
+        ))
+        .id();
+
+    // The first update only starts the clock.
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::ZERO));
+    app.update();
+    (app, player)
+}
+
+/// Left at a random spot near the player whenever a dash starts,
+/// standing in for the game's seeded spawns.
+#[derive(Component)]
+struct Marker;
+
+fn drop_marker_on_dash(
+    dashes: Query<&Transform, Added<Dash>>,
+    mut rng: ResMut<GameRng>,
+    mut commands: Commands,
+) {
+    for transform in &dashes {
+        let position = point_near(rng.gameplay(), transform.translation.truncate(), 100.0);
+        commands.spawn((
+            Marker,
+            TransformBundle::from_transform(Transform::from_translation(position.extend(0.0))),
+        ));
+    }
+}
+
+fn marker_positions(app: &mut App) -> Vec<Vec3> {
+    app.world_mut()
+        .query_filtered::<&Transform, With<Marker>>()
+        .iter(app.world())
+        .map(|transform| transform.translation)
+        .collect()
+}
+
+/// Frame times that jitter like a real game's.
+fn frame_time(frame: u32) -> Duration {
+    Duration::from_micros(7_000 + u64::from(frame * 7919 % 26_000))
+}
+
+fn send_key(app: &mut App, key_code: KeyCode, logical_key: Key, state: ButtonState) {
+    app.world_mut().send_event(KeyboardInput {
+        key_code,
+        logical_key,
+        state,
+        window: Entity::PLACEHOLDER,
+    });
+}
+
+fn simulated_position(app: &App, player: Entity) -> Vec3 {
+    app.world()
+        .get::<InterpolateTranslation>(player)
+        .and_then(InterpolateTranslation::simulated)
+        .expect("the player should have been simulated")
+}
+
+#[test]
+fn replay_reproduces_recorded_run() {
+    let (mut app, player) = headless_app();
+    app.insert_resource(GameRng::from_seed(42));
+    app.insert_resource(ReplayRecorder(Replay::new(42, LevelKey::Field)));
+    for frame in 0..150 {
+        match frame {
+            0 => send_key(
+                &mut app,
+                KeyCode::KeyD,
+                Key::Character("d".into()),
+                ButtonState::Pressed,
+            ),
+            30 | 90 => send_key(&mut app, KeyCode::Space, Key::Space, ButtonState::Pressed),
+            31 | 91 => send_key(&mut app, KeyCode::Space, Key::Space, ButtonState::Released),
+            60 => {
+                send_key(
+                    &mut app,
+                    KeyCode::KeyD,
+                    Key::Character("d".into()),
+                    ButtonState::Released,
+                );
+                send_key(
+                    &mut app,
+                    KeyCode::KeyW,
+                    Key::Character("w".into()),
+                    ButtonState::Pressed,
+                );
+            }
+            _ => {}
+        }
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(frame_time(frame)));
+        app.update();
+    }
+    let recorded_position = simulated_position(&app, player);
+    let recorded_markers = marker_positions(&mut app);
+    let ReplayRecorder(replay) = app.world_mut().remove_resource::<ReplayRecorder>().unwrap();
+
+    let dashes = replay
+        .frames
+        .iter()
+        .filter(|frame| frame.actions.just_pressed(Action::Dash))
+        .count();
+    assert_eq!(dashes, 2);
+    assert!(recorded_position.x > 0.0 && recorded_position.y > 0.0);
+    assert_eq!(recorded_markers.len(), dashes);
+
+    let path = std::env::temp_dir().join("bevy_jam_5_replay_test.ron");
+    replay.write(&path).unwrap();
+    let replay = Replay::read(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    let (mut app, player) = headless_app();
+    let frames = replay.frames.len();
+    app.world_mut().trigger(StartPlayback(replay));
+    for _ in 0..frames {
+        app.update();
+    }
+
+    assert!(!app.world().contains_resource::<ReplayPlayback>());
+    assert_eq!(app.world().resource::<GameRng>().seed(), 42);
+    assert_eq!(simulated_position(&app, player), recorded_position);
+    assert_eq!(marker_positions(&mut app), recorded_markers);
+}
@@ -0,0 +1,343 @@
+//! Scripted runs through the headless [`TestApp`].
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_jam_5::{
+    game::{
+        assets::LevelKey,
+        camera::CameraFollow,
+        cycle::DayCycle,
+        enemies::{Enemy, EnemyArchetype},
+        health::{Damage, DamageType, Health},
+        items::Item,
+        levels::SelectedLevel,
+        plant::{Plant, Soil},
+        rng::LaunchSeed,
+        rounds::Round,
+        spawn::{enemy::SpawnEnemy, level::Level, terrain::Wall},
+        stats::RunStats,
+        status::{ApplyStatus, Stacking, StatusEffect, StatusEffects, StatusKind},
+    },
+    test_support::{count, TestApp},
+};
+
+#[test]
+fn watered_weed_hatches_into_an_enemy() {
+    let mut app = TestApp::new();
+    // A short day, so the weed is ripe by nightfall and hatches soon after.
+    app.world_mut().resource_mut::<DayCycle>().day_duration = Duration::from_secs(5);
+    app.start_run();
+
+    // The player starts on a soil tile with weed seeds selected.
+    // The planter reaches 200 units, just as far as the edges of the four
+    // neighbouring tiles, but not their corners.
+    app.tap(KeyCode::Digit1);
+    app.advance(Duration::from_millis(100));
+    assert_eq!(
+        app.count::<With<Plant>>(),
+        5,
+        "weeds should be planted on the player's tile and its neighbours"
+    );
+
+    // Switching to the watering can waters the soil it's on, and using it waters it again.
+    app.tap(KeyCode::Digit2);
+    app.advance(Duration::from_millis(100));
+    app.tap(KeyCode::KeyE);
+    let moisture = app
+        .world_mut()
+        .query::<&Soil>()
+        .iter(app.world())
+        .find(|soil| soil.plant.is_some())
+        .map(|soil| soil.moisture)
+        .unwrap();
+    assert!(
+        moisture > 0.9,
+        "the soil should be soaked, but was {moisture}"
+    );
+
+    let hatched = app.advance_until(Duration::from_secs(15), |world| {
+        count::<With<Enemy>>(world) > 0
+    });
+    assert!(hatched, "the weed should hatch into an enemy at night");
+    assert_eq!(app.count::<With<Plant>>(), 0);
+}
+
+/// Where the enemies hatched from a watered weed appear in a run with `seed`.
+fn hatched_enemy_positions(seed: u64) -> Vec<Vec2> {
+    let mut app = TestApp::new();
+    app.world_mut().insert_resource(LaunchSeed(Some(seed)));
+    app.world_mut().resource_mut::<DayCycle>().day_duration = Duration::from_secs(5);
+    app.start_run();
+    app.tap(KeyCode::Digit1);
+    app.tap(KeyCode::Digit2);
+    app.tap(KeyCode::KeyE);
+
+    let hatched = app.advance_until(Duration::from_secs(15), |world| {
+        count::<With<Enemy>>(world) > 0
+    });
+    assert!(hatched, "the weed should hatch into an enemy at night");
+    app.world_mut()
+        .query_filtered::<&Transform, With<Enemy>>()
+        .iter(app.world())
+        .map(|transform| transform.translation.truncate())
+        .collect()
+}
+
+#[test]
+fn same_seed_hatches_the_same_enemies() {
+    let first = hatched_enemy_positions(7);
+    assert_eq!(first, hatched_enemy_positions(7));
+    assert_ne!(
+        first,
+        hatched_enemy_positions(8),
+        "enemies should scatter differently with another seed"
+    );
+}
+
+#[test]
+fn shooting_an_enemy_drops_an_item() {
+    let mut app = TestApp::new();
+    app.start_run();
+    app.world_mut().trigger(SpawnEnemy {
+        position: Vec2::new(600.0, 0.0),
+        archetype: EnemyArchetype::Chaser,
+        health: Some(Health::full(2.0)),
+    });
+    app.tap(KeyCode::Digit3);
+
+    let enemy_health = |world: &mut World| {
+        world
+            .query_filtered::<&Health, With<Enemy>>()
+            .iter(world)
+            .next()
+            .map(|health| health.current)
+    };
+    let hit = app.advance_until(
+        Duration::from_secs(1),
+        |world| matches!(enemy_health(world), Some(health) if health < 2.0),
+    );
+    assert!(hit, "the gun should hit the enemy");
+    assert_eq!(
+        enemy_health(app.world_mut()),
+        Some(1.0),
+        "one bullet shouldn't kill it"
+    );
+
+    let killed = app.advance_until(Duration::from_secs(1), |world| {
+        count::<With<Enemy>>(world) == 0
+    });
+    assert!(killed, "the second bullet should kill the enemy");
+    assert_eq!(
+        app.count::<With<Item>>(),
+        1,
+        "the enemy should drop an item"
+    );
+}
+
+#[test]
+fn poison_stacks_up_to_its_limit_and_wears_off() {
+    let mut app = TestApp::new();
+    app.start_run();
+    app.world_mut().trigger(SpawnEnemy {
+        position: Vec2::new(2000.0, 0.0),
+        archetype: EnemyArchetype::Chaser,
+        health: Some(Health::full(10.0)),
+    });
+    app.update();
+    let enemy = app
+        .world_mut()
+        .query_filtered::<Entity, With<Enemy>>()
+        .single(app.world());
+
+    let poison = StatusEffect {
+        kind: StatusKind::Poison,
+        duration_secs: 2.5,
+        strength: 0.5,
+        tick_interval_secs: 1.0,
+        stacking: Stacking::Stack { max: 2 },
+    };
+    for _ in 0..3 {
+        app.world_mut().trigger_targets(
+            ApplyStatus {
+                effect: poison.clone(),
+                source: None,
+            },
+            enemy,
+        );
+    }
+    app.advance(Duration::from_secs(3));
+
+    // Two stacks, each ticking twice before wearing off.
+    assert_eq!(
+        app.world()
+            .get::<Health>(enemy)
+            .map(|health| health.current),
+        Some(8.0)
+    );
+    assert!(app.world().get::<StatusEffects>(enemy).is_none());
+}
+
+#[test]
+fn health_bar_shows_once_an_enemy_is_hurt() {
+    let mut app = TestApp::new();
+    app.start_run();
+    app.world_mut().trigger(SpawnEnemy {
+        position: Vec2::new(2000.0, 0.0),
+        archetype: EnemyArchetype::Chaser,
+        health: Some(Health::full(10.0)),
+    });
+    app.update();
+    let enemy = app
+        .world_mut()
+        .query_filtered::<Entity, With<Enemy>>()
+        .single(app.world());
+    let bar_visibility = |app: &mut TestApp| {
+        app.update();
+        app.world_mut()
+            .query::<(&Name, &Parent, &Visibility)>()
+            .iter(app.world())
+            .find(|(name, parent, _)| name.as_str() == "Health Bar" && parent.get() == enemy)
+            .map(|(_, _, visibility)| *visibility)
+    };
+    assert_eq!(bar_visibility(&mut app), Some(Visibility::Hidden));
+
+    app.world_mut().trigger_targets(
+        Damage {
+            amount: 4.0,
+            damage_type: DamageType::Physical,
+            source: None,
+        },
+        enemy,
+    );
+    assert_eq!(bar_visibility(&mut app), Some(Visibility::Inherited));
+}
+
+#[test]
+fn selected_level_is_spawned_from_its_layout() {
+    let mut app = TestApp::new();
+    app.insert_resource(SelectedLevel(LevelKey::Garden));
+    app.start_run();
+
+    let level = app.world().resource::<Level>();
+    assert_eq!(level.key, LevelKey::Garden);
+    assert!(!level.wrap);
+    assert_eq!(level.bounds.size(), Vec2::new(17.0, 11.0) * 300.0);
+    // The border, and the four walls splitting the garden beds.
+    assert_eq!(app.count::<With<Wall>>(), 56);
+    // The player starts on soil in the middle.
+    let player = app.player();
+    assert_eq!(
+        app.world()
+            .get::<Transform>(player)
+            .map(|transform| transform.translation.truncate()),
+        Some(Vec2::ZERO)
+    );
+}
+
+#[test]
+fn zero_tick_interval_does_not_tick_endlessly() {
+    let mut app = TestApp::new();
+    app.start_run();
+    app.world_mut().trigger(SpawnEnemy {
+        position: Vec2::new(2000.0, 0.0),
+        archetype: EnemyArchetype::Chaser,
+        health: Some(Health::full(10.0)),
+    });
+    app.update();
+    let enemy = app
+        .world_mut()
+        .query_filtered::<Entity, With<Enemy>>()
+        .single(app.world());
+
+    app.world_mut().trigger_targets(
+        ApplyStatus {
+            effect: StatusEffect {
+                kind: StatusKind::Burn,
+                duration_secs: 0.2,
+                strength: 0.5,
+                tick_interval_secs: 0.0,
+                stacking: Stacking::Refresh,
+            },
+            source: None,
+        },
+        enemy,
+    );
+    app.advance(Duration::from_millis(500));
+
+    let health = app
+        .world()
+        .get::<Health>(enemy)
+        .map(|health| health.current);
+    assert!(
+        health.is_some_and(|health| health > 5.0),
+        "a few ticks at most, got {health:?}"
+    );
+}
+
+#[test]
+fn camera_follows_the_player_within_the_level() {
+    let mut app = TestApp::new();
+    app.insert_resource(SelectedLevel(LevelKey::Garden));
+    app.start_run();
+    let camera_x = |app: &mut TestApp| {
+        app.world_mut()
+            .query_filtered::<&Transform, With<CameraFollow>>()
+            .single(app.world())
+            .translation
+            .x
+    };
+    assert_eq!(camera_x(&mut app), 0.0);
+
+    // Far to the right, but still on a garden bed.
+    let player = app.player();
+    app.world_mut()
+        .get_mut::<Transform>(player)
+        .unwrap()
+        .translation
+        .x = 2000.0;
+    app.advance(Duration::from_secs(2));
+
+    // Half of the 1280 pixel wide window at 2.5 units per pixel is 1600 units,
+    // which is as close as the camera gets to the edge of the garden at 2550.
+    let x = camera_x(&mut app);
+    assert!(
+        x > 900.0 && x <= 950.0,
+        "the camera should follow, but is at {x}"
+    );
+}
+
+#[test]
+fn only_kills_by_the_player_are_credited() {
+    let mut app = TestApp::new();
+    app.start_run();
+    for x in [2000.0, -2000.0] {
+        app.world_mut().trigger(SpawnEnemy {
+            position: Vec2::new(x, 0.0),
+            archetype: EnemyArchetype::Chaser,
+            health: Some(Health::full(1.0)),
+        });
+    }
+    app.update();
+    let enemies: Vec<Entity> = app
+        .world_mut()
+        .query_filtered::<Entity, With<Enemy>>()
+        .iter(app.world())
+        .collect();
+    let player = app.player();
+
+    for (enemy, source) in enemies.into_iter().zip([Some(player), None]) {
+        app.world_mut().trigger_targets(
+            Damage {
+                amount: 5.0,
+                damage_type: DamageType::Physical,
+                source,
+            },
+            enemy,
+        );
+    }
+    app.update();
+
+    assert_eq!(app.world().resource::<Round>().stats.kills, 1);
+    assert_eq!(app.world().resource::<RunStats>().kills, 1);
+}